@@ -0,0 +1,315 @@
+use crate::vault::{self, Key};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The answer to one part of a day.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i64),
+    String(String),
+    /// Capital letters drawn with `#` and `.`, one line per row. They are
+    /// read back as text when displayed.
+    Letters(String),
+    /// Day 25 only has one puzzle.
+    NoPart2,
+    /// The part gave up on the input, for the given reason.
+    Unsolved(String),
+}
+
+impl Answer {
+    /// Reads an answer as written by its `Display` implementation.
+    pub fn parse(s: &str) -> Self {
+        if s == "-" {
+            Answer::NoPart2
+        } else if let Ok(n) = s.parse() {
+            Answer::Integer(n)
+        } else {
+            Answer::String(s.to_string())
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            Answer::Integer(n) => Value::from(*n),
+            Answer::NoPart2 | Answer::Unsolved(_) => Value::Null,
+            _ => Value::from(self.to_string()),
+        }
+    }
+
+    /// The value to post to Advent Of Code, if there is one.
+    pub fn submission(&self) -> Option<String> {
+        match self {
+            Answer::NoPart2 | Answer::Unsolved(_) => None,
+            _ => Some(self.to_string()),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => n.fmt(f),
+            Answer::String(s) => s.fmt(f),
+            Answer::Letters(art) => ocr(art).fmt(f),
+            Answer::NoPart2 => "-".fmt(f),
+            Answer::Unsolved(_) => "unsolved".fmt(f),
+        }
+    }
+}
+
+// Answers are compared the way they are submitted
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    // Compared as text anyway, like Answer::parse reads them
+                    i64::try_from(n)
+                        .map_or_else(|_| Answer::String(n.to_string()), Answer::Integer)
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i32, i64, u8, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::String(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::String(s.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Result<T, String>> for Answer {
+    fn from(result: Result<T, String>) -> Self {
+        result.map_or_else(Answer::Unsolved, Into::into)
+    }
+}
+
+// The 4x6 font used by the puzzles drawing letters
+const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+fn ocr(art: &str) -> String {
+    let rows: Vec<Vec<u8>> = art
+        .lines()
+        .map(|l| {
+            l.bytes()
+                .map(|b| if b == b'#' { b'#' } else { b'.' })
+                .collect()
+        })
+        .collect();
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+
+    (0..width.div_ceil(5))
+        .map(|i| {
+            let glyph: Vec<u8> = rows
+                .iter()
+                .flat_map(|r| (5 * i..5 * i + 4).map(|x| *r.get(x).unwrap_or(&b'.')))
+                .collect();
+
+            FONT.iter()
+                .find(|(_, g)| g.as_bytes() == glyph)
+                .map_or('?', |&(c, _)| c)
+        })
+        .collect()
+}
+
+#[test]
+fn check_ocr() {
+    const ART: &str = "#..#.####.#....#.....##.
+#..#.#....#....#....#..#
+####.###..#....#....#..#
+#..#.#....#....#....#..#
+#..#.#....#....#....#..#
+#..#.####.####.####..##.";
+
+    assert_eq!(Answer::Letters(ART.to_string()).to_string(), "HELLO");
+    assert_eq!(Answer::Letters(ART.to_string()), Answer::from("HELLO"));
+}
+
+/// Known answers, stored with one line per day: the day number followed by
+/// both answers, `?` standing for an unknown answer. The file may be
+/// encrypted like the inputs, it then stays encrypted when saved. Comments
+/// (lines starting with `#`) and blank lines are kept with the day after
+/// them.
+#[derive(Default)]
+pub struct Answers {
+    path: PathBuf,
+    answers: BTreeMap<u32, [Option<Answer>; 2]>,
+    comments: BTreeMap<u32, Vec<String>>,
+    trailer: Vec<String>,
+    modified: bool,
+    key: Option<Key>,
+}
+
+impl Answers {
+    pub fn load(path: &Path, key: Option<&Key>) -> io::Result<Self> {
+        let mut answers = Answers {
+            path: path.to_path_buf(),
+            ..Default::default()
+        };
+
+        if let Some(content) = vault::read(path, key)? {
+            let content = String::from_utf8(content)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+            answers.read(&content)?;
+
+            if !path.exists() {
+                answers.key = key.cloned();
+            }
+        }
+
+        Ok(answers)
+    }
+
+    fn read(&mut self, content: &str) -> io::Result<()> {
+        let mut comments = vec![];
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                comments.push(line.to_string());
+                continue;
+            }
+
+            let fields: Vec<&str> = trimmed.split_whitespace().collect();
+            let day = fields[0].parse().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: bad line \"{trimmed}\"", self.path.display()),
+                )
+            })?;
+            let part = |i: usize| match fields.get(i) {
+                None | Some(&"?") => None,
+                Some(s) => Some(Answer::parse(s)),
+            };
+
+            self.answers.insert(day, [part(1), part(2)]);
+
+            if !comments.is_empty() {
+                self.comments.entry(day).or_default().append(&mut comments);
+            }
+        }
+
+        self.trailer = comments;
+
+        Ok(())
+    }
+
+    pub fn get(&self, day: u32, part: usize) -> Option<&Answer> {
+        self.answers.get(&day)?[part - 1].as_ref()
+    }
+
+    pub fn set(&mut self, day: u32, part: usize, answer: Answer) {
+        self.answers.entry(day).or_default()[part - 1] = Some(answer);
+        self.modified = true;
+    }
+
+    /// Writes the answers back if some were added.
+    pub fn save(&self) -> io::Result<()> {
+        if !self.modified {
+            return Ok(());
+        }
+
+        let content = self.write()?;
+
+        match &self.key {
+            Some(key) => fs::write(
+                vault::encrypted_path(&self.path),
+                vault::encrypt(key, &content)?,
+            ),
+            None => fs::write(&self.path, content),
+        }
+    }
+
+    fn write(&self) -> io::Result<Vec<u8>> {
+        let mut content = vec![];
+
+        for (day, parts) in &self.answers {
+            for comment in self.comments.get(day).into_iter().flatten() {
+                writeln!(content, "{comment}")?;
+            }
+
+            let [part1, part2] = parts.each_ref().map(|p| match p {
+                Some(answer) => answer.to_string(),
+                None => String::from("?"),
+            });
+
+            writeln!(content, "{day:0>2} {part1} {part2}")?;
+        }
+
+        for comment in &self.trailer {
+            writeln!(content, "{comment}")?;
+        }
+
+        Ok(content)
+    }
+}
+
+#[test]
+fn check_answers() {
+    const ANSWERS: &str = "# Answers of alice
+
+01 11 31
+# Found by hand
+03 161 ?
+# The end
+";
+    let mut answers = Answers::default();
+
+    answers.read(ANSWERS).unwrap();
+    answers.set(2, 1, Answer::from(2));
+    answers.set(3, 2, Answer::from(u64::MAX));
+
+    assert_eq!(
+        String::from_utf8(answers.write().unwrap()).unwrap(),
+        "# Answers of alice
+
+01 11 31
+02 2 ?
+# Found by hand
+03 161 18446744073709551615
+# The end
+"
+    );
+    assert_eq!(
+        answers.get(3, 2),
+        Some(&Answer::parse("18446744073709551615"))
+    );
+}
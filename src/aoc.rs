@@ -0,0 +1,270 @@
+use curl::easy::{Easy, List};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Where the puzzles of this year are.
+pub const URL: &str = "https://adventofcode.com/2024";
+
+/// The User-Agent sent with every request, so that the Advent Of Code
+/// maintainers know where the traffic comes from and whom to contact.
+pub fn user_agent(contact: Option<&str>) -> String {
+    let agent = concat!(
+        "advent_2024/",
+        env!("CARGO_PKG_VERSION"),
+        " (Advent Of Code 2024 solutions in Rust)"
+    );
+
+    match contact {
+        Some(contact) => format!("{agent} {contact}"),
+        None => String::from(agent),
+    }
+}
+
+// Gives the body of the page, unless the reply is an error page
+fn get(url: &str, user_agent: &str, session: Option<&str>) -> io::Result<Vec<u8>> {
+    let mut handle = Easy::new();
+    let mut body = vec![];
+
+    handle.useragent(user_agent)?;
+    if let Some(session) = session {
+        handle.cookie(&format!("session={session}"))?;
+    }
+    handle.url(url)?;
+
+    {
+        let mut transfer = handle.transfer();
+
+        transfer.write_function(|data| {
+            body.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+
+    // Before the puzzle unlocks, or with an expired session, the reply is
+    // an error page that must not be taken for the input
+    match handle.response_code()? {
+        200 => Ok(body),
+        code => Err(io::Error::other(HttpError {
+            url: url.to_string(),
+            code,
+            body: String::from_utf8_lossy(&body).trim().to_string(),
+        })),
+    }
+}
+
+/// An error page sent by the server.
+#[derive(Debug)]
+pub struct HttpError {
+    url: String,
+    code: u32,
+    body: String,
+}
+
+impl Display for HttpError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unable to download {}: HTTP {}, {}",
+            self.url, self.code, self.body
+        )
+    }
+}
+
+impl Error for HttpError {}
+
+/// Whether a request may succeed when tried again: the network failed, the
+/// server is in trouble (5xx) or asks to slow down (429). Other error pages,
+/// like those of an expired session, and local errors stay as they are.
+pub fn is_transient(error: &io::Error) -> bool {
+    let Some(error) = error.get_ref() else {
+        return false;
+    };
+
+    if let Some(error) = error.downcast_ref::<HttpError>() {
+        return error.code == 429 || error.code >= 500;
+    }
+
+    error.downcast_ref::<curl::Error>().is_some_and(|error| {
+        error.is_couldnt_resolve_host()
+            || error.is_couldnt_connect()
+            || error.is_operation_timedout()
+            || error.is_ssl_connect_error()
+            || error.is_partial_file()
+            || error.is_got_nothing()
+            || error.is_send_error()
+            || error.is_recv_error()
+    })
+}
+
+pub(crate) fn download_input(
+    url: &str,
+    user_agent: &str,
+    session: &str,
+    day_number: u32,
+    path: &Path,
+) -> io::Result<()> {
+    let body = get(
+        &format!("{url}/day/{day_number}/input"),
+        user_agent,
+        Some(session),
+    )?;
+
+    File::create(path)?.write_all(&body)
+}
+
+/// The page of the puzzle, with the second part only when logged in and
+/// the first one solved.
+pub(crate) fn download_puzzle(
+    url: &str,
+    user_agent: &str,
+    session: Option<&str>,
+    day_number: u32,
+) -> io::Result<String> {
+    let body = get(&format!("{url}/day/{day_number}"), user_agent, session)?;
+
+    String::from_utf8(body).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// The JSON of a private leaderboard, which needs the session of one of
+/// its members.
+pub(crate) fn download_leaderboard(
+    url: &str,
+    user_agent: &str,
+    session: &str,
+    id: &str,
+) -> io::Result<String> {
+    let body = get(
+        &format!("{url}/leaderboard/private/view/{id}.json"),
+        user_agent,
+        Some(session),
+    )?;
+
+    String::from_utf8(body).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// What Advent Of Code replied to a submitted answer.
+pub enum Verdict {
+    Right,
+    Wrong(String),
+    TooSoon(String),
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right answer"),
+            Verdict::Wrong(message) => write!(f, "wrong answer: {message}"),
+            Verdict::TooSoon(message) => write!(f, "too soon: {message}"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unknown(message) => write!(f, "unexpected reply: {message}"),
+        }
+    }
+}
+
+// Keeps the text of the <article> holding the reply, without its markup
+fn article_text(html: &str) -> String {
+    let article = match (html.find("<article>"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn submit_answer(
+    url: &str,
+    user_agent: &str,
+    session: &str,
+    day_number: u32,
+    part: usize,
+    answer: &str,
+) -> io::Result<Verdict> {
+    let mut handle = Easy::new();
+    let mut headers = List::new();
+    let mut body = vec![];
+    let form = format!(
+        "level={part}&answer={}",
+        handle.url_encode(answer.as_bytes())
+    );
+
+    headers.append("Content-Type: application/x-www-form-urlencoded")?;
+
+    handle.useragent(user_agent)?;
+    handle.cookie(&format!("session={session}"))?;
+    handle.url(&format!("{url}/day/{day_number}/answer"))?;
+    handle.http_headers(headers)?;
+    handle.post_fields_copy(form.as_bytes())?;
+
+    {
+        let mut transfer = handle.transfer();
+
+        transfer.write_function(|data| {
+            body.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+
+    let text = article_text(&String::from_utf8_lossy(&body));
+
+    Ok(if text.contains("That's the right answer") {
+        Verdict::Right
+    } else if text.contains("That's not the right answer") {
+        Verdict::Wrong(text)
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::TooSoon(text)
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(text)
+    })
+}
+
+#[test]
+fn check_article_text() {
+    const HTML: &str = "<html><main><article><p>That's the right answer!  You are\n\
+        <em>one gold star</em> closer.</p></article></main></html>";
+
+    assert_eq!(
+        article_text(HTML),
+        "That's the right answer! You are one gold star closer."
+    );
+}
+
+#[test]
+fn check_transient() {
+    let page = |code| {
+        io::Error::other(HttpError {
+            url: format!("{URL}/day/1/input"),
+            code,
+            body: String::new(),
+        })
+    };
+
+    assert!(is_transient(&page(503)));
+    assert!(is_transient(&page(429)));
+    assert!(!is_transient(&page(400)));
+    assert!(!is_transient(&page(404)));
+    assert!(!is_transient(&io::Error::from(
+        io::ErrorKind::PermissionDenied
+    )));
+    assert!(is_transient(&io::Error::from(curl::Error::new(7))));
+    assert!(!is_transient(&io::Error::from(curl::Error::new(3))));
+}
@@ -0,0 +1,128 @@
+use crate::Format;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the project-local configuration file.
+pub const FILENAME: &str = "advent.toml";
+
+/// Settings read from the configuration file, every one of them optional.
+/// Command line arguments take precedence over them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub session: Option<String>,
+    pub inputs: Option<PathBuf>,
+    pub answers: Option<PathBuf>,
+    pub threads: Option<usize>,
+    pub format: Option<Format>,
+    pub url: Option<String>,
+    /// Where the puzzle pages are kept.
+    pub cache: Option<PathBuf>,
+    /// Never access the network, see `--offline`.
+    pub offline: bool,
+    /// Email or repository sent along with the User-Agent of the requests.
+    pub contact: Option<String>,
+    /// Passphrase of the encrypted inputs and answers.
+    pub key: Option<String>,
+    /// Where the `--bench` runs are recorded.
+    pub history: Option<PathBuf>,
+    /// Private leaderboard shown when none is given to `leaderboard`.
+    pub leaderboard: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// What differs from one account to another, see `--profile`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub session: Option<String>,
+    pub inputs: Option<PathBuf>,
+    pub answers: Option<PathBuf>,
+}
+
+// $XDG_CONFIG_HOME/advent_2024/config.toml or ~/.config/advent_2024/config.toml
+fn user_config() -> Option<PathBuf> {
+    let directory = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(directory.join("advent_2024").join("config.toml"))
+}
+
+impl Config {
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+
+        Config::parse(&content).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {error}", path.display()),
+            )
+        })
+    }
+
+    /// Reads the given file or else `./advent.toml`, falling back to the
+    /// one in the user configuration directory. No file at all is fine.
+    pub fn load(path: Option<&Path>) -> io::Result<Self> {
+        if let Some(path) = path {
+            return Config::read(path);
+        }
+
+        let local = PathBuf::from(FILENAME);
+
+        for path in [Some(local), user_config()].into_iter().flatten() {
+            if path.exists() {
+                return Config::read(&path);
+            }
+        }
+
+        Ok(Config::default())
+    }
+}
+
+#[test]
+fn check_config() {
+    const CONFIG: &str = r#"
+session = "53616c746564"
+inputs = "../inputs"
+threads = 4
+format = "json"
+offline = true
+key = "s3cr3t"
+
+[profiles.alice]
+session = "616c696365"
+inputs = "inputs/alice"
+
+[profiles.bob]
+answers = "answers/bob.txt"
+"#;
+    let config = Config::parse(CONFIG).unwrap();
+
+    assert_eq!(config.session.as_deref(), Some("53616c746564"));
+    assert_eq!(config.inputs, Some(PathBuf::from("../inputs")));
+    assert_eq!(config.answers, None);
+    assert_eq!(config.threads, Some(4));
+    assert_eq!(config.format, Some(Format::Json));
+    assert!(config.offline);
+    assert_eq!(config.key.as_deref(), Some("s3cr3t"));
+    assert_eq!(
+        config.profiles.keys().collect::<Vec<_>>(),
+        vec!["alice", "bob"]
+    );
+    assert_eq!(
+        config.profiles["alice"].session.as_deref(),
+        Some("616c696365")
+    );
+    assert_eq!(config.profiles["bob"].inputs, None);
+
+    assert!(Config::parse("sesion = \"typo\"").is_err());
+}
@@ -1 +1 @@
-automod::dir!("src/days/");
+automod::dir!(pub "src/days/");
@@ -2,12 +2,11 @@ use std::collections::HashMap;
 use std::io::{BufRead, Lines};
 use std::iter::zip;
 
-fn resolve<T>(lines: Lines<T>) -> (u32, u32)
+fn parse<T>(lines: Lines<T>) -> (Vec<u32>, Vec<u32>)
 where
     T: BufRead,
 {
     let (mut left, mut right) = (vec![], vec![]);
-    let mut counts: HashMap<u32, u32> = HashMap::new();
 
     for line in lines {
         let line = line.unwrap();
@@ -16,25 +15,35 @@ where
 
         left.push(a);
         right.push(b);
-
-        *counts.entry(b).or_default() += 1;
     }
 
-    let numbers = left.clone();
+    (left, right)
+}
+
+fn part1((left, right): &(Vec<u32>, Vec<u32>)) -> u32 {
+    let mut left = left.clone();
+    let mut right = right.clone();
 
     left.sort_unstable();
     right.sort_unstable();
 
-    (
-        zip(left, right).fold(0, |acc, (a, b)| acc + a.abs_diff(b)),
-        numbers.iter().fold(0, |acc, e| {
-            if let Some(count) = counts.get(e) {
-                acc + e * count
-            } else {
-                acc
-            }
-        }),
-    )
+    zip(left, right).fold(0, |acc, (a, b)| acc + a.abs_diff(b))
+}
+
+fn part2((left, right): &(Vec<u32>, Vec<u32>)) -> u32 {
+    let mut counts: HashMap<u32, u32> = HashMap::new();
+
+    for &b in right {
+        *counts.entry(b).or_default() += 1;
+    }
+
+    left.iter().fold(0, |acc, e| {
+        if let Some(count) = counts.get(e) {
+            acc + e * count
+        } else {
+            acc
+        }
+    })
 }
 
 #[test]
@@ -47,15 +56,9 @@ fn check() {
 3   3";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (11, 31));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (11, 31));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -26,33 +26,39 @@ fn check_is_safe() {
     assert!(!is_safe(&[8, 6, 4, 4, 1]));
 }
 
-fn resolve<T>(lines: Lines<T>) -> (u32, u32)
-where
-    T: BufRead,
-{
-    lines.fold((0, 0), |(p1, p2), line| {
-        let numbers: Vec<i32> = line
-            .unwrap()
-            .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
-
-        if is_safe(&numbers) {
-            (p1 + 1, p2 + 1)
-        } else if (0..numbers.len()).any(|n| {
+fn is_safe_with_dampener(report: &[i32]) -> bool {
+    is_safe(report)
+        || (0..report.len()).any(|n| {
             is_safe(
-                &numbers
+                &report
                     .iter()
                     .enumerate()
                     .filter_map(|(i, &e)| if i != n { Some(e) } else { None })
                     .collect::<Vec<_>>(),
             )
-        }) {
-            (p1, p2 + 1)
-        } else {
-            (p1, p2)
-        }
-    })
+        })
+}
+
+fn parse<T>(lines: Lines<T>) -> Vec<Vec<i32>>
+where
+    T: BufRead,
+{
+    lines
+        .map(|line| {
+            line.unwrap()
+                .split(' ')
+                .map(|s| s.parse().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+fn part1(reports: &[Vec<i32>]) -> usize {
+    reports.iter().filter(|r| is_safe(r)).count()
+}
+
+fn part2(reports: &[Vec<i32>]) -> usize {
+    reports.iter().filter(|r| is_safe_with_dampener(r)).count()
 }
 
 #[test]
@@ -65,15 +71,9 @@ fn check() {
 1 3 6 7 9";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (2, 4));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (2, 4));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
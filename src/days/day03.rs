@@ -1,43 +1,60 @@
 use regex::Regex;
 use std::io::{BufRead, Lines};
 
-fn resolve<T>(lines: Lines<T>) -> (u32, u32, bool)
+enum Instruction {
+    Do,
+    Dont,
+    Mul(u32),
+}
+
+fn parse<T>(lines: Lines<T>) -> Vec<Instruction>
 where
     T: BufRead,
 {
     let re = Regex::new(r"do\(\)|don\'t\(\)|mul\((\d{1,3}),(\d{1,3})\)").unwrap();
 
-    lines.fold((0, 0, true), |(part1, part2, multiply), line| {
-        re.captures_iter(&line.unwrap())
-            .fold((part1, part2, multiply), |(p1, p2, m), c| {
-                match c.get(0).unwrap().as_str() {
-                    "do()" => (p1, p2, true),
-                    "don't()" => (p1, p2, false),
-                    _ => {
-                        let v = c.get(1).unwrap().as_str().parse::<u32>().unwrap()
-                            * c.get(2).unwrap().as_str().parse::<u32>().unwrap();
-
-                        (p1 + v, if m { p2 + v } else { p2 }, m)
-                    }
-                }
-            })
+    lines
+        .flat_map(|line| {
+            re.captures_iter(&line.unwrap())
+                .map(|c| match c.get(0).unwrap().as_str() {
+                    "do()" => Instruction::Do,
+                    "don't()" => Instruction::Dont,
+                    _ => Instruction::Mul(
+                        c.get(1).unwrap().as_str().parse::<u32>().unwrap()
+                            * c.get(2).unwrap().as_str().parse::<u32>().unwrap(),
+                    ),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn part1(instructions: &[Instruction]) -> u32 {
+    instructions.iter().fold(0, |p1, i| match i {
+        Instruction::Mul(v) => p1 + v,
+        _ => p1,
     })
 }
 
+fn part2(instructions: &[Instruction]) -> u32 {
+    instructions
+        .iter()
+        .fold((0, true), |(p2, m), i| match i {
+            Instruction::Do => (p2, true),
+            Instruction::Dont => (p2, false),
+            Instruction::Mul(v) => (if m { p2 + v } else { p2 }, m),
+        })
+        .0
+}
+
 #[test]
 fn check() {
     const TEST: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (161, 48, true));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (161, 48));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,8 +1,11 @@
 use std::io::{BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 10 } else { 140 };
+// The examples are smaller, the rest of their grid is never matched
+const SIZE: usize = 140;
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+type Grid = [[u8; SIZE]; SIZE];
+
+fn parse<T>(lines: Lines<T>) -> Grid
 where
     T: BufRead,
 {
@@ -11,10 +14,14 @@ where
     for (y, line) in lines.enumerate() {
         let line = line.unwrap();
 
-        grid[y].clone_from_slice(line.as_bytes());
+        grid[y][..line.len()].clone_from_slice(line.as_bytes());
     }
 
-    let mut part1 = 0;
+    grid
+}
+
+fn part1(grid: &Grid) -> usize {
+    let mut count = 0;
 
     for y in 0..SIZE {
         for x in 0..SIZE {
@@ -23,7 +30,7 @@ where
                 let horizontal = &grid[y][x..x + 4];
 
                 if horizontal == b"XMAS" || horizontal == b"SAMX" {
-                    part1 += 1;
+                    count += 1;
                 }
             }
 
@@ -32,7 +39,7 @@ where
                 let vertical = [grid[y][x], grid[y + 1][x], grid[y + 2][x], grid[y + 3][x]];
 
                 if vertical == *b"XMAS" || vertical == *b"SAMX" {
-                    part1 += 1;
+                    count += 1;
                 }
             }
 
@@ -47,7 +54,7 @@ where
                 ];
 
                 if antislash == *b"XMAS" || antislash == *b"SAMX" {
-                    part1 += 1;
+                    count += 1;
                 }
 
                 // diagonal /
@@ -59,13 +66,17 @@ where
                 ];
 
                 if slash == *b"XMAS" || slash == *b"SAMX" {
-                    part1 += 1;
+                    count += 1;
                 }
             }
         }
     }
 
-    let mut part2 = 0;
+    count
+}
+
+fn part2(grid: &Grid) -> usize {
+    let mut count = 0;
 
     for x in 1..SIZE - 1 {
         for y in 1..SIZE - 1 {
@@ -73,12 +84,12 @@ where
                 && grid[y - 1][x - 1] + grid[y + 1][x + 1] == b'S' + b'M'
                 && grid[y - 1][x + 1] + grid[y + 1][x - 1] == b'S' + b'M'
             {
-                part2 += 1;
+                count += 1;
             }
         }
     }
 
-    (part1, part2)
+    count
 }
 
 #[test]
@@ -95,15 +106,9 @@ MAMMMXMMMM
 MXMXAXMASX";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (18, 9));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (18, 9));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -20,7 +20,17 @@ impl Page {
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (u32, u32)
+type Updates = (HashMap<u32, Page>, Vec<Vec<u32>>);
+
+fn is_sorted(pages_list: &[&Page]) -> bool {
+    pages_list.is_sorted_by(|a, b| a.before.contains(&b.number))
+}
+
+fn pages_list<'a>(pages: &'a HashMap<u32, Page>, update: &[u32]) -> Vec<&'a Page> {
+    update.iter().map(|n| pages.get(n).unwrap()).collect()
+}
+
+fn parse<T>(lines: Lines<T>) -> Updates
 where
     T: BufRead,
 {
@@ -44,18 +54,37 @@ where
         pages.entry(splitted[1]).or_insert(Page::new(splitted[1]));
     }
 
-    lines.fold((0, 0), |(p1, p2), line| {
-        let pages_list: Vec<&Page> = line
-            .unwrap()
-            .split(',')
-            .map(|s| pages.get(&s.parse().unwrap()).unwrap())
-            .collect();
+    let updates = lines
+        .map(|line| {
+            line.unwrap()
+                .split(',')
+                .map(|s| s.parse().unwrap())
+                .collect()
+        })
+        .collect();
 
-        if pages_list.is_sorted_by(|a, b| a.before.contains(&b.number)) {
-            (p1 + pages_list[pages_list.len() / 2].number, p2)
+    (pages, updates)
+}
+
+fn part1((pages, updates): &Updates) -> u32 {
+    updates.iter().fold(0, |p1, update| {
+        let pages_list = pages_list(pages, update);
+
+        if is_sorted(&pages_list) {
+            p1 + pages_list[pages_list.len() / 2].number
         } else {
-            let mut pages_list = pages_list;
+            p1
+        }
+    })
+}
 
+fn part2((pages, updates): &Updates) -> u32 {
+    updates.iter().fold(0, |p2, update| {
+        let mut pages_list = pages_list(pages, update);
+
+        if is_sorted(&pages_list) {
+            p2
+        } else {
             pages_list.sort_unstable_by(|a, b| {
                 if a.before.contains(&b.number) {
                     Ordering::Less
@@ -64,7 +93,7 @@ where
                 }
             });
 
-            (p1, p2 + pages_list[pages_list.len() / 2].number)
+            p2 + pages_list[pages_list.len() / 2].number
         }
     })
 }
@@ -101,15 +130,9 @@ fn check() {
 97,13,75,29,47";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (143, 123));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (143, 123));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
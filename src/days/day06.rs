@@ -1,8 +1,8 @@
 use rayon::prelude::*;
-use std::io::{BufRead, Lines};
+use std::io::{self, BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 10 } else { 130 };
-const S: i32 = SIZE as i32;
+// Large enough for the input, the examples only use a corner of the grid
+const SIZE: usize = 130;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum Direction {
@@ -35,6 +35,15 @@ impl Direction {
         }
     }
 
+    fn jump(&self, (x, y): (i32, i32), steps: i32) -> (i32, i32) {
+        match self {
+            Direction::Up => (x, y - steps),
+            Direction::Right => (x + steps, y),
+            Direction::Down => (x, y + steps),
+            Direction::Left => (x - steps, y),
+        }
+    }
+
     fn mask(&self) -> u8 {
         match self {
             Direction::Up => 0b_0001,
@@ -55,15 +64,16 @@ fn check_direction() {
 
 struct Map {
     grid: [[u8; SIZE]; SIZE],
+    size: i32,
 }
 
 impl Map {
-    fn new(grid: [[u8; SIZE]; SIZE]) -> Self {
-        Map { grid }
+    fn new(grid: [[u8; SIZE]; SIZE], size: i32) -> Self {
+        Map { grid, size }
     }
 
     fn get(&self, (x, y): (i32, i32)) -> Option<u8> {
-        if x < 0 || y < 0 || x >= S || y >= S {
+        if x < 0 || y < 0 || x >= self.size || y >= self.size {
             None
         } else {
             Some(self.grid[y as usize][x as usize])
@@ -78,10 +88,10 @@ impl Map {
 #[test]
 fn check_map() {
     let grid = [[4; SIZE]; SIZE];
-    let mut map = Map::new(grid);
+    let mut map = Map::new(grid, 10);
 
     assert_eq!(map.get((-1, 0)), None);
-    assert_eq!(map.get((0, S)), None);
+    assert_eq!(map.get((0, 10)), None);
     assert_eq!(map.get((1, 1)), Some(4));
 
     map.set((1, 1), 99);
@@ -121,6 +131,133 @@ fn check_loop(
     false
 }
 
+// No obstacle before the edge of the map
+const EXIT: u16 = u16::MAX;
+
+fn index((x, y): (i32, i32)) -> usize {
+    y as usize * SIZE + x as usize
+}
+
+fn position(index: u16) -> (i32, i32) {
+    (
+        (index as usize % SIZE) as i32,
+        (index as usize / SIZE) as i32,
+    )
+}
+
+/// Where the guard stops before the next obstacle, for each position and
+/// direction, so that a walk jumps from a turn to the next one.
+struct Jumps {
+    stops: Vec<[u16; 4]>,
+}
+
+impl Jumps {
+    fn new(map: &Map) -> Self {
+        let mut stops = vec![[EXIT; 4]; SIZE * SIZE];
+        let size = map.size as usize;
+        let is_wall = |cell: usize| map.grid[cell / SIZE][cell % SIZE] == b'#';
+        let (up, right, down, left) = (0, 1, 2, 3);
+
+        // Row by row, the stops going up and left are below and right of
+        // the last obstacles seen, for each column and in the row
+        let mut columns = [EXIT; SIZE];
+
+        for y in 0..size {
+            let mut row = EXIT;
+
+            for (x, column) in columns.iter_mut().enumerate().take(size) {
+                let cell = y * SIZE + x;
+
+                if is_wall(cell) {
+                    *column = (cell + SIZE) as u16;
+                    row = (cell + 1) as u16;
+                } else {
+                    stops[cell][up] = *column;
+                    stops[cell][left] = row;
+                }
+            }
+        }
+
+        // Then backwards for down and right. The stops before obstacles on
+        // the first row or column are never read
+        let mut columns = [EXIT; SIZE];
+
+        for y in (0..size).rev() {
+            let mut row = EXIT;
+
+            for (x, column) in columns.iter_mut().enumerate().take(size).rev() {
+                let cell = y * SIZE + x;
+
+                if is_wall(cell) {
+                    *column = (cell as u16).wrapping_sub(SIZE as u16);
+                    row = (cell as u16).wrapping_sub(1);
+                } else {
+                    stops[cell][down] = *column;
+                    stops[cell][right] = row;
+                }
+            }
+        }
+
+        Jumps { stops }
+    }
+
+    // How far `to` is straight ahead of `from`
+    fn distance(direction: Direction, from: (i32, i32), to: (i32, i32)) -> Option<i32> {
+        let (along, across) = match direction {
+            Direction::Up => (from.1 - to.1, from.0 - to.0),
+            Direction::Right => (to.0 - from.0, from.1 - to.1),
+            Direction::Down => (to.1 - from.1, from.0 - to.0),
+            Direction::Left => (from.0 - to.0, from.1 - to.1),
+        };
+
+        (across == 0 && along > 0).then_some(along)
+    }
+
+    // The same as check_loop, the blocker stopping the guard when it is on
+    // the way to the obstacle of the table. The directions seen at each
+    // turn are kept in `dirs`, the turns in `turns`, both left cleared for
+    // the next check
+    fn check_loop(
+        &self,
+        blocker: (i32, i32),
+        mut guard: (i32, i32),
+        mut direction: Direction,
+        dirs: &mut [u8],
+        turns: &mut Vec<usize>,
+    ) -> bool {
+        let found = loop {
+            let stop = self.stops[index(guard)][direction as usize];
+            let blocked = Jumps::distance(direction, guard, blocker).filter(|&to_blocker| {
+                stop == EXIT
+                    || to_blocker <= Jumps::distance(direction, guard, position(stop)).unwrap_or(0)
+            });
+
+            guard = match blocked {
+                Some(to_blocker) => direction.jump(guard, to_blocker - 1),
+                None if stop == EXIT => break false,
+                None => position(stop),
+            };
+
+            let entry = &mut dirs[index(guard)];
+            let m = direction.mask();
+
+            if *entry & m == m {
+                break true;
+            }
+
+            *entry |= m;
+            turns.push(index(guard));
+            direction = direction.turn_right();
+        };
+
+        for turn in turns.drain(..) {
+            dirs[turn] = 0;
+        }
+
+        found
+    }
+}
+
 struct PossibleBlock {
     block_position: (i32, i32),
     position: (i32, i32),
@@ -139,19 +276,37 @@ impl PossibleBlock {
     fn check_loop(&self, map: &Map) -> bool {
         check_loop(map, self.block_position, self.position, self.direction)
     }
+
+    fn check_loop_jumps(&self, jumps: &Jumps, dirs: &mut [u8], turns: &mut Vec<usize>) -> bool {
+        jumps.check_loop(
+            self.block_position,
+            self.position,
+            self.direction,
+            dirs,
+            turns,
+        )
+    }
+}
+
+struct Lab {
+    grid: [[u8; SIZE]; SIZE],
+    size: i32,
+    guard: (i32, i32),
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+fn parse<T>(lines: Lines<T>) -> Lab
 where
     T: BufRead,
 {
     let mut grid = [[0; SIZE]; SIZE];
     let mut guard = (0i32, 0i32);
-    let mut direction = Direction::new();
+    let mut size = 0;
 
     for (y, line) in lines.enumerate() {
         let line = line.unwrap();
 
+        size = y as i32 + 1;
+
         for (x, &c) in line.as_bytes().iter().enumerate() {
             if c == b'^' {
                 guard = (x as i32, y as i32);
@@ -160,8 +315,16 @@ where
         }
     }
 
-    let mut map = Map::new(grid);
+    Lab { grid, size, guard }
+}
+
+// Walks the guard out of the lab, every visited position (but the starting
+// one) is a candidate for a new obstruction.
+fn walk(lab: &Lab) -> (Map, Vec<PossibleBlock>) {
+    let mut map = Map::new(lab.grid, lab.size);
     let mut blocks: Vec<PossibleBlock> = vec![];
+    let mut guard = lab.guard;
+    let mut direction = Direction::new();
 
     loop {
         let next_position = direction.next(guard);
@@ -187,13 +350,94 @@ where
         }
     }
 
-    (
-        blocks.len() + 1,
-        blocks
-            .into_par_iter()
-            .filter(|block| block.check_loop(&map))
-            .count(),
-    )
+    (map, blocks)
+}
+
+fn part1(lab: &Lab) -> usize {
+    let (_, blocks) = walk(lab);
+
+    blocks.len() + 1
+}
+
+fn part2(lab: &Lab) -> usize {
+    let (map, blocks) = walk(lab);
+    let jumps = Jumps::new(&map);
+
+    blocks
+        .into_par_iter()
+        .map_init(
+            || (vec![0; SIZE * SIZE], vec![]),
+            |(dirs, turns), block| block.check_loop_jumps(&jumps, dirs, turns),
+        )
+        .filter(|&found| found)
+        .count()
+}
+
+// Walks cell by cell, to compare with the jump tables
+fn part2_cells(lab: &Lab) -> usize {
+    let (map, blocks) = walk(lab);
+
+    blocks
+        .into_par_iter()
+        .filter(|block| block.check_loop(&map))
+        .count()
+}
+
+// A map from a linear congruential generator, with an obstacle in about one
+// position out of fifteen. The longest walk out of a few maps is kept, for
+// more positions to block
+fn generate(seed: u64, size: usize) -> Lab {
+    let mut seed = seed;
+    let mut random = |n: u64| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) % n
+    };
+    let mut lab = || loop {
+        let mut grid = [[b'.'; SIZE]; SIZE];
+
+        for line in grid.iter_mut().take(size) {
+            for c in line.iter_mut().take(size) {
+                if random(15) == 0 {
+                    *c = b'#';
+                }
+            }
+        }
+
+        let guard = (random(size as u64) as i32, random(size as u64) as i32);
+
+        grid[guard.1 as usize][guard.0 as usize] = b'^';
+
+        // The walk of part 1 never ends when the guard is already in a loop
+        if !check_loop(
+            &Map::new(grid, size as i32),
+            (-1, -1),
+            guard,
+            Direction::new(),
+        ) {
+            break Lab {
+                grid,
+                size: size as i32,
+                guard,
+            };
+        }
+    };
+
+    (0..50).map(|_| lab()).max_by_key(part1).unwrap()
+}
+
+/// Writes a random map of the size of the inputs, the same for the same
+/// `seed`, to time the days on more than the input.
+pub fn lab<W: io::Write>(seed: u64, mut out: W) -> io::Result<()> {
+    let lab = generate(seed, SIZE);
+
+    for line in &lab.grid {
+        out.write_all(line)?;
+        writeln!(out)?;
+    }
+
+    Ok(())
 }
 
 #[test]
@@ -210,15 +454,37 @@ fn check() {
 ......#...";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (41, 6));
+    let input = parse(Cursor::new(TEST).lines());
+
+    assert_eq!((part1(&input), part2(&input)), (41, 6));
+    assert_eq!(part2_cells(&input), 6);
 }
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+#[test]
+fn check_jumps() {
+    for (seed, size, loops) in [(5, 10, 3), (7, 40, 8), (8, 60, 70)] {
+        let lab = generate(seed, size);
+
+        assert_eq!(part2(&lab), loops);
+        assert_eq!(part2_cells(&lab), loops);
+    }
+
+    let mut grid = [[0; SIZE]; SIZE];
+
+    for (line, row) in ["..#", "#..", ".^."].iter().zip(grid.iter_mut()) {
+        row[..3].copy_from_slice(line.as_bytes());
+    }
+
+    let jumps = Jumps::new(&Map::new(grid, 3));
+    let up = Direction::Up as usize;
+
+    assert_eq!(jumps.stops[index((1, 2))], [EXIT; 4]);
+    assert_eq!(jumps.stops[index((2, 2))][up], index((2, 1)) as u16);
+    assert_eq!(
+        jumps.stops[index((1, 1))][Direction::Left as usize],
+        index((1, 1)) as u16
+    );
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
+advent_2024::day!("cells", parse, part1, part2_cells);
@@ -72,45 +72,37 @@ fn check_operation_recurs() {
     assert!(!operation_recurs(161011, 16, &[10, 13], opes1));
 }
 
-fn resolve<T>(lines: Lines<T>) -> (u64, u64)
+fn calibration(equations: &[Vec<u64>], operations: &[Operation]) -> u64 {
+    equations
+        .par_iter()
+        .filter(|equation| operation_recurs(equation[0], equation[1], &equation[2..], operations))
+        .map(|equation| equation[0])
+        .sum()
+}
+
+fn parse<T>(lines: Lines<T>) -> Vec<Vec<u64>>
 where
     T: BufRead,
 {
-    let equations: Vec<Vec<u64>> = lines
+    lines
         .map(|line| {
             line.unwrap()
                 .split(&[':', ' '])
                 .filter_map(|s| s.parse().ok())
                 .collect()
         })
-        .collect();
+        .collect()
+}
 
-    equations
-        .into_par_iter()
-        .fold(
-            || (0, 0),
-            |(p1, p2), equation| {
-                let p1_ok = operation_recurs(
-                    equation[0],
-                    equation[1],
-                    &equation[2..],
-                    &[Operation::Add, Operation::Multiply],
-                );
-                let p2_ok = p1_ok
-                    || operation_recurs(
-                        equation[0],
-                        equation[1],
-                        &equation[2..],
-                        &[Operation::Add, Operation::Multiply, Operation::Concatenate],
-                    );
-
-                (
-                    if p1_ok { p1 + equation[0] } else { p1 },
-                    if p2_ok { p2 + equation[0] } else { p2 },
-                )
-            },
-        )
-        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+fn part1(equations: &[Vec<u64>]) -> u64 {
+    calibration(equations, &[Operation::Add, Operation::Multiply])
+}
+
+fn part2(equations: &[Vec<u64>]) -> u64 {
+    calibration(
+        equations,
+        &[Operation::Add, Operation::Multiply, Operation::Concatenate],
+    )
 }
 
 #[test]
@@ -126,15 +118,9 @@ fn check() {
 292: 11 6 16 20";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (3749, 11387));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (3749, 11387));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,79 +1,107 @@
 use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, Lines};
 
-fn compute_antinode(pos: (i32, i32), v: (i32, i32), k: i32) -> Option<(i32, i32)> {
-    const S: i32 = if cfg!(test) { 12 } else { 50 };
+fn compute_antinode(
+    (width, height): (i32, i32),
+    pos: (i32, i32),
+    v: (i32, i32),
+    k: i32,
+) -> Option<(i32, i32)> {
     let antinode = (pos.0 + k * v.0, pos.1 + k * v.1);
 
-    if antinode.0 < 0 || antinode.1 < 0 || antinode.0 >= S || antinode.1 >= S {
+    if antinode.0 < 0 || antinode.1 < 0 || antinode.0 >= width || antinode.1 >= height {
         None
     } else {
         Some(antinode)
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+struct Antennas {
+    frequencies: HashMap<u8, Vec<(i32, i32)>>,
+    size: (i32, i32),
+}
+
+fn parse<T>(lines: Lines<T>) -> Antennas
 where
     T: BufRead,
 {
-    let mut antennas: HashMap<u8, Vec<(i32, i32)>> = HashMap::new();
+    let mut frequencies: HashMap<u8, Vec<(i32, i32)>> = HashMap::new();
+    let mut size = (0, 0);
 
     for (y, line) in lines.enumerate() {
         let line = line.unwrap();
 
+        size = (size.0.max(line.len() as i32), y as i32 + 1);
+
         for (x, &c) in line.as_bytes().iter().enumerate() {
             if c != b'.' {
                 let p = (x as i32, y as i32);
 
-                antennas.entry(c).or_default().push(p);
+                frequencies.entry(c).or_default().push(p);
             }
         }
     }
 
-    let (antinodes, antinodes2) = antennas.values().fold(
-        (
-            HashSet::new(),
-            antennas
-                .values()
-                .flat_map(|v| v.iter().copied())
-                .collect::<HashSet<_>>(),
-        ),
-        |(mut h1, mut h2), positions| {
-            let len = positions.len();
-
-            for i in 0..len {
-                for j in i + 1..len {
-                    let v = (
-                        positions[j].0 - positions[i].0,
-                        positions[j].1 - positions[i].1,
-                    );
-
-                    let mut k = 1;
-                    while let Some(a) = compute_antinode(positions[j], v, k) {
-                        if k == 1 {
-                            h1.insert(a);
-                        }
-                        h2.insert(a);
-
-                        k += 1;
+    Antennas { frequencies, size }
+}
+
+// Antinodes are found at `k` times the vector between two antennas, only the
+// first ones (k = 1 and k = -2) count unless `resonant` is set.
+fn antinodes(antennas: &Antennas, resonant: bool) -> HashSet<(i32, i32)> {
+    let mut antinodes = if resonant {
+        antennas
+            .frequencies
+            .values()
+            .flat_map(|v| v.iter().copied())
+            .collect::<HashSet<_>>()
+    } else {
+        HashSet::new()
+    };
+
+    for positions in antennas.frequencies.values() {
+        let len = positions.len();
+
+        for i in 0..len {
+            for j in i + 1..len {
+                let v = (
+                    positions[j].0 - positions[i].0,
+                    positions[j].1 - positions[i].1,
+                );
+
+                let mut k = 1;
+                while let Some(a) = compute_antinode(antennas.size, positions[j], v, k) {
+                    antinodes.insert(a);
+
+                    if !resonant {
+                        break;
                     }
 
-                    let mut k = -2;
-                    while let Some(a) = compute_antinode(positions[j], v, k) {
-                        if k == -2 {
-                            h1.insert(a);
-                        }
-                        h2.insert(a);
+                    k += 1;
+                }
+
+                let mut k = -2;
+                while let Some(a) = compute_antinode(antennas.size, positions[j], v, k) {
+                    antinodes.insert(a);
 
-                        k -= 1;
+                    if !resonant {
+                        break;
                     }
+
+                    k -= 1;
                 }
             }
-            (h1, h2)
-        },
-    );
+        }
+    }
+
+    antinodes
+}
+
+fn part1(antennas: &Antennas) -> usize {
+    antinodes(antennas, false).len()
+}
 
-    (antinodes.len(), antinodes2.len())
+fn part2(antennas: &Antennas) -> usize {
+    antinodes(antennas, true).len()
 }
 
 #[test]
@@ -92,15 +120,23 @@ fn check() {
 ............";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (14, 34));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (14, 34));
+
+    // A smaller map of the text
+    const TEST2: &str = "T.........
+...T......
+.T........
+..........
+..........
+..........
+..........
+..........
+..........
+..........";
+
+    assert_eq!(part2(&parse(Cursor::new(TEST2).lines())), 9);
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -93,7 +93,13 @@ fn resolve_part2(
         })
 }
 
-fn resolve<T>(mut lines: Lines<T>) -> (usize, usize)
+struct Disk {
+    disk: Vec<u8>,
+    file_blocks: Vec<(usize, usize)>,
+    empty_blocks: [BinaryHeap<Reverse<usize>>; 10],
+}
+
+fn parse<T>(mut lines: Lines<T>) -> Disk
 where
     T: BufRead,
 {
@@ -121,10 +127,19 @@ where
         offset += size;
     }
 
-    (
-        resolve_part1(&mut disk),
-        resolve_part2(&file_blocks, &mut empty_blocks),
-    )
+    Disk {
+        disk,
+        file_blocks,
+        empty_blocks,
+    }
+}
+
+fn part1(disk: &Disk) -> usize {
+    resolve_part1(&mut disk.disk.clone())
+}
+
+fn part2(disk: &Disk) -> usize {
+    resolve_part2(&disk.file_blocks, &mut disk.empty_blocks.clone())
 }
 
 #[test]
@@ -132,15 +147,9 @@ fn check() {
     const TEST: &str = "2333133121414131402";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (1928, 2858));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (1928, 2858));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
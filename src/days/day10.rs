@@ -1,6 +1,7 @@
 use std::io::{BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 8 } else { 53 };
+// The examples are smaller, the rest of their grid is never climbed
+const SIZE: usize = 53;
 const S: i32 = SIZE as i32;
 const DIRS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, 1), (0, -1)];
 
@@ -41,7 +42,9 @@ fn trailheads(zero: &(i32, i32), grid: &[[u8; SIZE]; SIZE]) -> (usize, usize) {
     (p1, p2)
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+type TopographicMap = ([[u8; SIZE]; SIZE], Vec<(i32, i32)>);
+
+fn parse<T>(lines: Lines<T>) -> TopographicMap
 where
     T: BufRead,
 {
@@ -62,11 +65,15 @@ where
         });
     }
 
-    zeroes.into_iter().fold((0, 0), |(part1, part2), zero| {
-        let (p1, p2) = trailheads(&zero, &grid);
+    (grid, zeroes)
+}
+
+fn part1((grid, zeroes): &TopographicMap) -> usize {
+    zeroes.iter().map(|zero| trailheads(zero, grid).0).sum()
+}
 
-        (part1 + p1, part2 + p2)
-    })
+fn part2((grid, zeroes): &TopographicMap) -> usize {
+    zeroes.iter().map(|zero| trailheads(zero, grid).1).sum()
 }
 
 #[test]
@@ -122,22 +129,16 @@ fn check() {
 56789.";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST1).lines()).0, 2);
-    assert_eq!(resolve(Cursor::new(TEST2).lines()).0, 4);
-    assert_eq!(resolve(Cursor::new(TEST3).lines()).0, 3);
-    assert_eq!(resolve(Cursor::new(TEST4).lines()).1, 3);
-    assert_eq!(resolve(Cursor::new(TEST5).lines()).1, 13);
-    assert_eq!(resolve(Cursor::new(TEST6).lines()).1, 227);
+    assert_eq!(part1(&parse(Cursor::new(TEST1).lines())), 2);
+    assert_eq!(part1(&parse(Cursor::new(TEST2).lines())), 4);
+    assert_eq!(part1(&parse(Cursor::new(TEST3).lines())), 3);
+    assert_eq!(part2(&parse(Cursor::new(TEST4).lines())), 3);
+    assert_eq!(part2(&parse(Cursor::new(TEST5).lines())), 13);
+    assert_eq!(part2(&parse(Cursor::new(TEST6).lines())), 227);
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (36, 81));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (36, 81));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
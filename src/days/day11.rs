@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::io::{BufRead, Lines};
 
-fn split_stone(n: usize) -> Option<usize> {
+fn split_stone(n: u64) -> Option<u64> {
     let mut count = 0;
     let mut temp = n;
     let mut mul = 1;
@@ -31,10 +31,9 @@ fn check_split_stone() {
     assert_eq!(split_stone(1000), Some(100));
 }
 
-#[cfg(test)]
-// tried this implementation, but it isn't faster
-fn split_stone2(n: usize) -> Option<usize> {
-    const CACHE: &[usize] = &[
+// tried this implementation, but it isn't faster: it is kept as a variant
+fn split_stone2(n: u64) -> Option<u64> {
+    const CACHE: &[u64] = &[
         0,
         10,
         100,
@@ -48,6 +47,13 @@ fn split_stone2(n: usize) -> Option<usize> {
         10_000_000_000,
         100_000_000_000,
         1_000_000_000_000,
+        10_000_000_000_000,
+        100_000_000_000_000,
+        1_000_000_000_000_000,
+        10_000_000_000_000_000,
+        100_000_000_000_000_000,
+        1_000_000_000_000_000_000,
+        10_000_000_000_000_000_000,
     ];
 
     let index = match CACHE.binary_search(&n) {
@@ -74,13 +80,15 @@ fn check_split_stone2() {
     assert_eq!(split_stone2(1000), Some(100));
 }
 
-fn blink(current: &HashMap<usize, usize>, next: &mut HashMap<usize, usize>) {
+type Split = fn(u64) -> Option<u64>;
+
+fn blink(current: &HashMap<u64, u64>, next: &mut HashMap<u64, u64>, split: Split) {
     next.clear();
 
     for (&stone, &count) in current {
         if stone == 0 {
             *next.entry(1).or_default() += count;
-        } else if let Some(mul) = split_stone(stone) {
+        } else if let Some(mul) = split(stone) {
             *next.entry(stone / mul).or_default() += count;
             *next.entry(stone % mul).or_default() += count;
         } else {
@@ -89,33 +97,47 @@ fn blink(current: &HashMap<usize, usize>, next: &mut HashMap<usize, usize>) {
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+fn blinks(stones: &HashMap<u64, u64>, count: usize, split: Split) -> u64 {
+    let mut next = HashMap::new();
+    let mut current = stones.clone();
+
+    for _ in 0..count {
+        blink(&current, &mut next, split);
+
+        std::mem::swap(&mut current, &mut next);
+    }
+
+    current.values().sum()
+}
+
+fn parse<T>(lines: Lines<T>) -> HashMap<u64, u64>
 where
     T: BufRead,
 {
-    let mut next = HashMap::new();
-    let mut current = HashMap::new();
+    let mut stones = HashMap::new();
     let line = lines.last().unwrap().unwrap();
 
     for s in line.split_whitespace() {
-        *current.entry(s.parse().unwrap()).or_default() += 1;
+        *stones.entry(s.parse().unwrap()).or_default() += 1;
     }
 
-    for _ in 0..25 {
-        blink(&current, &mut next);
-
-        std::mem::swap(&mut current, &mut next);
-    }
+    stones
+}
 
-    let p1 = current.values().sum();
+fn part1(stones: &HashMap<u64, u64>) -> u64 {
+    blinks(stones, 25, split_stone)
+}
 
-    for _ in 25..75 {
-        blink(&current, &mut next);
+fn part2(stones: &HashMap<u64, u64>) -> u64 {
+    blinks(stones, 75, split_stone)
+}
 
-        std::mem::swap(&mut current, &mut next);
-    }
+fn part1_binary_search(stones: &HashMap<u64, u64>) -> u64 {
+    blinks(stones, 25, split_stone2)
+}
 
-    (p1, current.values().sum())
+fn part2_binary_search(stones: &HashMap<u64, u64>) -> u64 {
+    blinks(stones, 75, split_stone2)
 }
 
 #[test]
@@ -123,15 +145,19 @@ fn check() {
     const TEST: &str = "125 17";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (55312, 65601038650482));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (55312, 65601038650482));
+    assert_eq!(
+        (part1_binary_search(&input), part2_binary_search(&input)),
+        (55312, 65601038650482)
+    );
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
+advent_2024::day!(
+    "binary_search",
+    parse,
+    part1_binary_search,
+    part2_binary_search
+);
@@ -2,8 +2,8 @@ use rayon::prelude::*;
 use std::collections::HashMap;
 use std::io::{BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 10 } else { 140 };
-const S: i32 = SIZE as i32;
+// Large enough for the input, the examples only use a corner of the grid
+const SIZE: usize = 140;
 
 fn price(plots: &[((i32, i32), usize)]) -> (usize, usize) {
     const DELTAS: [(i32, i32, u8); 4] = [
@@ -39,6 +39,7 @@ fn price(plots: &[((i32, i32), usize)]) -> (usize, usize) {
 
 fn found_region_dfs(
     grid: &mut [[u8; SIZE]; SIZE],
+    (width, height): (i32, i32),
     point: (i32, i32),
     garden: u8,
 ) -> Vec<((i32, i32), usize)> {
@@ -52,7 +53,7 @@ fn found_region_dfs(
         for (dx, dy) in DIRS {
             let p = (x + dx, y + dy);
 
-            if p.0 < 0 || p.1 < 0 || p.0 >= S || p.1 >= S {
+            if p.0 < 0 || p.1 < 0 || p.0 >= width || p.1 >= height {
                 continue;
             }
 
@@ -86,48 +87,56 @@ fn check_found_region_dfs() {
     grid[1][1] = b'A';
     grid[2][2] = b'A';
 
-    let r = found_region_dfs(&mut grid, (0, 0), b'A');
+    let r = found_region_dfs(&mut grid, (10, 10), (0, 0), b'A');
 
     assert_eq!(r, vec![((0, 0), 1), ((1, 0), 3), ((1, 1), 1), ((2, 0), 1)]);
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+type Region = Vec<((i32, i32), usize)>;
+
+fn parse<T>(lines: Lines<T>) -> Vec<Region>
 where
     T: BufRead,
 {
     let mut grid: [[u8; SIZE]; SIZE] = [[0; SIZE]; SIZE];
-    let mut regions: Vec<Vec<((i32, i32), usize)>> = vec![];
+    let mut regions: Vec<Region> = vec![];
+    let (mut width, mut height) = (0, 0);
 
     for (y, line) in lines.enumerate() {
         let line = line.unwrap();
 
-        grid[y].copy_from_slice(line.as_bytes());
+        grid[y][..line.len()].copy_from_slice(line.as_bytes());
+        width = width.max(line.len());
+        height = y + 1;
     }
 
-    for y in 0..SIZE {
-        for x in 0..SIZE {
+    for y in 0..height {
+        for x in 0..width {
             let garden = grid[y][x];
 
             // check if its uppercase
             if (garden & (1 << 5)) == 0 {
                 grid[y][x] |= 1 << 5;
 
-                regions.push(found_region_dfs(&mut grid, (x as i32, y as i32), garden))
+                regions.push(found_region_dfs(
+                    &mut grid,
+                    (width as i32, height as i32),
+                    (x as i32, y as i32),
+                    garden,
+                ))
             }
         }
     }
 
     regions
-        .into_par_iter()
-        .fold(
-            || (0, 0),
-            |(p1, p2), r| {
-                let price = price(&r);
-
-                (p1 + r.len() * price.0, p2 + r.len() * price.1)
-            },
-        )
-        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+}
+
+fn part1(regions: &[Region]) -> usize {
+    regions.par_iter().map(|r| r.len() * price(r).0).sum()
+}
+
+fn part2(regions: &[Region]) -> usize {
+    regions.par_iter().map(|r| r.len() * price(r).1).sum()
 }
 
 #[test]
@@ -144,15 +153,19 @@ MIIISIJEEE
 MMMISSJEEE";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (1930, 1206));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (1930, 1206));
+
+    // A smaller garden, without the cells past its edges as a region
+    const TEST2: &str = "AAAA
+BBCD
+BBCC
+EEEC";
+
+    let input = parse(Cursor::new(TEST2).lines());
+
+    assert_eq!((part1(&input), part2(&input)), (140, 80));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
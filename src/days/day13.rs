@@ -15,7 +15,7 @@ impl Machine {
         Machine { ax, ay, bx, by }
     }
 
-    fn presses(&self, (prizex, prizey): (i64, i64)) -> (i64, i64) {
+    fn presses(&self, (prizex, prizey): (i64, i64)) -> i64 {
         // A * ax + B * bx = prizex
         // A * ay + B * by = prizey
         //
@@ -24,30 +24,22 @@ impl Machine {
         //
         // A * (ax * by - ay * bx) + 0 = prizex * by - prizey * bx
         //
-        let numerator1 = prizex * self.by - prizey * self.bx;
-        let numerator2 = numerator1 + 10000000000000 * (self.by - self.bx);
+        let numerator = prizex * self.by - prizey * self.bx;
         let denominator = self.ax * self.by - self.ay * self.bx;
 
-        (
-            if numerator1.signum() == denominator.signum() && numerator1 % denominator == 0 {
-                let a = numerator1 / denominator;
-                let b = (prizex - a * self.ax) / self.bx;
-                3 * a + b
-            } else {
-                0
-            },
-            if numerator2.signum() == denominator.signum() && numerator2 % denominator == 0 {
-                let a = numerator2 / denominator;
-                let b = (10_000_000_000_000 + prizex - a * self.ax) / self.bx;
-                3 * a + b
-            } else {
-                0
-            },
-        )
+        if numerator.signum() == denominator.signum() && numerator % denominator == 0 {
+            let a = numerator / denominator;
+            let b = (prizex - a * self.ax) / self.bx;
+            3 * a + b
+        } else {
+            0
+        }
     }
 }
 
-fn resolve<T>(mut lines: Lines<T>) -> (i64, i64)
+type Arcade = Vec<(Machine, (i64, i64))>;
+
+fn parse<T>(mut lines: Lines<T>) -> Arcade
 where
     T: BufRead,
 {
@@ -69,11 +61,20 @@ where
 
             (Machine::new(ax, ay, bx, by), (prizex, prizey))
         })
-        .fold((0, 0), |(p1, p2), (m, p)| {
-            let presses = m.presses(p);
+        .collect()
+}
 
-            (p1 + presses.0, p2 + presses.1)
-        })
+fn part1(arcade: &Arcade) -> i64 {
+    arcade.iter().map(|(m, p)| m.presses(*p)).sum()
+}
+
+fn part2(arcade: &Arcade) -> i64 {
+    const OFFSET: i64 = 10_000_000_000_000;
+
+    arcade
+        .iter()
+        .map(|(m, p)| m.presses((p.0 + OFFSET, p.1 + OFFSET)))
+        .sum()
 }
 
 #[test]
@@ -95,15 +96,9 @@ Button B: X+27, Y+71
 Prize: X=18641, Y=10279";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (480, 875318608908));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (480, 875318608908));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,8 +1,8 @@
 use regex::Regex;
 use std::io::{BufRead, Lines};
 
-const W: i32 = if cfg!(test) { 11 } else { 101 };
-const H: i32 = if cfg!(test) { 7 } else { 103 };
+// Width and height of the bathroom
+type Space = (i32, i32);
 
 #[derive(Debug, Clone)]
 struct Robot {
@@ -15,16 +15,16 @@ impl Robot {
         Robot { pos, velocity }
     }
 
-    fn step(&mut self, steps: i32) {
-        self.pos.0 = (self.pos.0 + steps * self.velocity.0).rem_euclid(W);
-        self.pos.1 = (self.pos.1 + steps * self.velocity.1).rem_euclid(H);
+    fn step(&mut self, steps: i32, (w, h): Space) {
+        self.pos.0 = (self.pos.0 + steps * self.velocity.0).rem_euclid(w);
+        self.pos.1 = (self.pos.1 + steps * self.velocity.1).rem_euclid(h);
     }
 }
 
 #[cfg(test)]
-fn print_bathroom(robots: &[Robot]) {
-    for y in 0..H {
-        for x in 0..W {
+fn print_bathroom(robots: &[Robot], (w, h): Space) {
+    for y in 0..h {
+        for x in 0..w {
             let mut found = false;
 
             for r in robots {
@@ -46,17 +46,17 @@ fn print_bathroom(robots: &[Robot]) {
 }
 
 #[cfg(not(test))]
-fn print_bathroom(_robots: &[Robot]) {}
+fn print_bathroom(_robots: &[Robot], _space: Space) {}
 
-fn compute_safety(robots: &[Robot]) -> usize {
+fn compute_safety(robots: &[Robot], (w, h): Space) -> usize {
     let mut squares = [0usize; 4];
 
     for r in robots {
-        let index = match r.pos.0.cmp(&(W / 2)) {
+        let index = match r.pos.0.cmp(&(w / 2)) {
             std::cmp::Ordering::Less => 0,
             std::cmp::Ordering::Greater => 2,
             std::cmp::Ordering::Equal => continue,
-        } + match r.pos.1.cmp(&(H / 2)) {
+        } + match r.pos.1.cmp(&(h / 2)) {
             std::cmp::Ordering::Less => 0,
             std::cmp::Ordering::Greater => 1,
             std::cmp::Ordering::Equal => continue,
@@ -68,19 +68,21 @@ fn compute_safety(robots: &[Robot]) -> usize {
     squares.iter().product()
 }
 
-fn steps(robots: &mut [Robot], steps: i32) {
+fn steps(robots: &mut [Robot], steps: i32, space: Space) {
     for r in robots.iter_mut() {
-        r.step(steps);
+        r.step(steps, space);
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, i32)
+type Bathroom = (Vec<Robot>, Space);
+
+fn parse<T>(lines: Lines<T>) -> Bathroom
 where
     T: BufRead,
 {
     let re = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
 
-    let robots: Vec<Robot> = lines
+    let robots = lines
         .map(|line| {
             let line = line.unwrap();
 
@@ -94,16 +96,31 @@ where
         })
         .collect();
 
+    (robots, advent_2024::parameter!((11, 7), (101, 103)))
+}
+
+fn part1((robots, space): &Bathroom) -> usize {
+    let mut robots = robots.to_vec();
+
+    steps(&mut robots, 100, *space);
+
+    print_bathroom(&robots, *space);
+
+    compute_safety(&robots, *space)
+}
+
+fn part2((robots, space): &Bathroom) -> i32 {
+    let (w, h) = *space;
     let mut min_safety = usize::MAX;
     let mut part2 = 0;
 
-    let mut robots_tree = robots.clone();
+    let mut robots_tree = robots.to_vec();
     let mut min_w = 0;
 
-    for i in 0..W {
-        steps(&mut robots_tree, 1);
+    for i in 0..w {
+        steps(&mut robots_tree, 1, *space);
 
-        let safety = compute_safety(&robots_tree);
+        let safety = compute_safety(&robots_tree, *space);
 
         if safety < min_safety {
             min_w = i + 1;
@@ -111,27 +128,21 @@ where
         }
     }
 
-    robots_tree = robots.clone();
-    steps(&mut robots_tree, min_w);
+    robots_tree = robots.to_vec();
+    steps(&mut robots_tree, min_w, *space);
 
-    for i in 0..H {
-        steps(&mut robots_tree, W);
+    for i in 0..h {
+        steps(&mut robots_tree, w, *space);
 
-        let safety = compute_safety(&robots_tree);
+        let safety = compute_safety(&robots_tree, *space);
 
         if safety < min_safety {
-            part2 = min_w + (i + 1) * W;
+            part2 = min_w + (i + 1) * w;
             min_safety = safety;
         }
     }
 
-    let mut robots = robots;
-
-    steps(&mut robots, 100);
-
-    print_bathroom(&robots);
-
-    (compute_safety(&robots), part2)
+    part2
 }
 
 #[test]
@@ -150,15 +161,7 @@ p=2,4 v=2,-3
 p=9,5 v=-3,-3";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()).0, 12);
-}
-
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!(part1(&parse(Cursor::new(TEST).lines())), 12);
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,6 +1,9 @@
 use std::io::{BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 10 } else { 50 };
+// The examples are smaller, their walls keep the robot in a corner
+const SIZE: usize = 50;
+
+type Grid = [[u8; 2 * SIZE]; SIZE];
 
 #[derive(Debug, Clone, Copy)]
 struct Point(usize, usize);
@@ -33,7 +36,7 @@ impl Point {
     }
 }
 
-fn walk(grid: &mut [[u8; 2 * SIZE]; SIZE], robot: Point, direction: u8) -> Point {
+fn walk(grid: &mut Grid, robot: Point, direction: u8) -> Point {
     let mut next = robot;
 
     loop {
@@ -53,7 +56,7 @@ fn walk(grid: &mut [[u8; 2 * SIZE]; SIZE], robot: Point, direction: u8) -> Point
     }
 }
 
-fn move_box_horizontaly(grid: &mut [[u8; 2 * SIZE]; SIZE], p: Point, direction: u8) -> bool {
+fn move_box_horizontaly(grid: &mut Grid, p: Point, direction: u8) -> bool {
     let g = grid[p.1][p.0];
 
     match g {
@@ -72,7 +75,7 @@ fn move_box_horizontaly(grid: &mut [[u8; 2 * SIZE]; SIZE], p: Point, direction:
     }
 }
 
-fn can_move_box_verticaly(grid: &[[u8; 2 * SIZE]; SIZE], p: Point, direction: u8) -> bool {
+fn can_move_box_verticaly(grid: &Grid, p: Point, direction: u8) -> bool {
     match grid[p.1][p.0] {
         b'#' => false,
         b'.' => true,
@@ -92,7 +95,7 @@ fn can_move_box_verticaly(grid: &[[u8; 2 * SIZE]; SIZE], p: Point, direction: u8
     }
 }
 
-fn move_box_verticaly(grid: &mut [[u8; 2 * SIZE]; SIZE], p: Point, direction: u8) {
+fn move_box_verticaly(grid: &mut Grid, p: Point, direction: u8) {
     match grid[p.1][p.0] {
         b'#' => (),
         b'.' => (),
@@ -126,7 +129,7 @@ fn move_box_verticaly(grid: &mut [[u8; 2 * SIZE]; SIZE], p: Point, direction: u8
     }
 }
 
-fn walk2(grid: &mut [[u8; 2 * SIZE]; SIZE], robot: Point, direction: u8) -> Point {
+fn walk2(grid: &mut Grid, robot: Point, direction: u8) -> Point {
     let next = robot.move_to(direction);
 
     if direction == b'<' || direction == b'>' {
@@ -147,7 +150,7 @@ fn walk2(grid: &mut [[u8; 2 * SIZE]; SIZE], robot: Point, direction: u8) -> Poin
 }
 
 #[cfg(test)]
-fn print_grid(grid: &[[u8; 2 * SIZE]; SIZE], robot: &Point) {
+fn print_grid(grid: &Grid, robot: &Point) {
     for (y, line) in grid.iter().enumerate() {
         for (x, &c) in line.iter().enumerate() {
             if robot.0 == x && robot.1 == y {
@@ -162,9 +165,9 @@ fn print_grid(grid: &[[u8; 2 * SIZE]; SIZE], robot: &Point) {
 }
 
 #[cfg(not(test))]
-fn print_grid(_grid: &[[u8; 2 * SIZE]; SIZE], _robot: &Point) {}
+fn print_grid(_grid: &Grid, _robot: &Point) {}
 
-fn gps_boxes(grid: &[[u8; 2 * SIZE]; SIZE]) -> usize {
+fn gps_boxes(grid: &Grid) -> usize {
     grid.iter().enumerate().fold(0, |acc, (y, line)| {
         line.iter().enumerate().fold(acc, |a, (x, &c)| {
             if c == b'[' || c == b'O' {
@@ -176,14 +179,23 @@ fn gps_boxes(grid: &[[u8; 2 * SIZE]; SIZE]) -> usize {
     })
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+struct Warehouse {
+    grid: Grid,
+    grid2: Grid,
+    robot: Point,
+    robot2: Point,
+    moves: Vec<u8>,
+}
+
+fn parse<T>(lines: Lines<T>) -> Warehouse
 where
     T: BufRead,
 {
-    let mut grid: [[u8; 2 * SIZE]; SIZE] = [[b'.'; 2 * SIZE]; SIZE];
-    let mut grid2: [[u8; 2 * SIZE]; SIZE] = [[b'.'; 2 * SIZE]; SIZE];
+    let mut grid: Grid = [[b'.'; 2 * SIZE]; SIZE];
+    let mut grid2: Grid = [[b'.'; 2 * SIZE]; SIZE];
     let mut robot = Point(0, 0);
     let mut robot2 = Point(0, 0);
+    let mut moves = vec![];
     let mut is_direction = false;
 
     for (y, line) in lines.enumerate() {
@@ -215,17 +227,43 @@ where
                 }
             }
         } else {
-            for &d in line.as_bytes().iter() {
-                robot = walk(&mut grid, robot, d);
-                robot2 = walk2(&mut grid2, robot2, d);
-            }
+            moves.extend_from_slice(line.as_bytes());
         }
     }
 
+    Warehouse {
+        grid,
+        grid2,
+        robot,
+        robot2,
+        moves,
+    }
+}
+
+fn part1(warehouse: &Warehouse) -> usize {
+    let mut grid = warehouse.grid;
+    let mut robot = warehouse.robot;
+
+    for &d in warehouse.moves.iter() {
+        robot = walk(&mut grid, robot, d);
+    }
+
+    print_grid(&grid, &robot);
+
+    gps_boxes(&grid)
+}
+
+fn part2(warehouse: &Warehouse) -> usize {
+    let mut grid = warehouse.grid2;
+    let mut robot = warehouse.robot2;
+
+    for &d in warehouse.moves.iter() {
+        robot = walk2(&mut grid, robot, d);
+    }
+
     print_grid(&grid, &robot);
-    print_grid(&grid2, &robot2);
 
-    (gps_boxes(&grid), gps_boxes(&grid2))
+    gps_boxes(&grid)
 }
 
 #[test]
@@ -272,17 +310,12 @@ vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
 v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST1).lines()).0, 2028);
-    assert_eq!(resolve(Cursor::new(TEST2).lines()).1, 105 + 207 + 306);
-    assert_eq!(resolve(Cursor::new(TEST3).lines()), (10092, 9021));
-}
+    assert_eq!(part1(&parse(Cursor::new(TEST1).lines())), 2028);
+    assert_eq!(part2(&parse(Cursor::new(TEST2).lines())), 105 + 207 + 306);
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    let input = parse(Cursor::new(TEST3).lines());
+
+    assert_eq!((part1(&input), part2(&input)), (10092, 9021));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,10 +1,28 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::BinaryHeap;
-use std::io::{BufRead, Lines};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 17 } else { 141 };
+// The examples are smaller, their walls keep the reindeer in a corner
+const SIZE: usize = 141;
 
 type Point = (u16, u16);
+// On the heap, two of them would not fit on the stack of a test
+type Scores = Vec<[[usize; 4]; SIZE]>;
+
+/// What turning a quarter and stepping forward add to the score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Costs {
+    pub turn: usize,
+    pub step: usize,
+}
+
+impl Costs {
+    pub const PUZZLE: Costs = Costs {
+        turn: 1000,
+        step: 1,
+    };
+}
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Direction {
@@ -14,6 +32,13 @@ enum Direction {
     Left = 3,
 }
 
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+];
+
 impl Direction {
     fn turn_right(&self) -> Self {
         match self {
@@ -50,6 +75,26 @@ impl Direction {
             Direction::Left => (p.0 + 1, p.1),
         }
     }
+
+    // The quarter turns to face the other direction
+    fn turns(&self, other: Direction) -> usize {
+        match (other as usize + 4 - *self as usize) % 4 {
+            3 => 1,
+            turns => turns,
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+        .fmt(f)
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -81,7 +126,7 @@ impl PartialOrd for State {
     }
 }
 
-fn dijkstra<F>(scores: &mut [[[usize; 4]; SIZE]; SIZE], starts: &[(Point, Direction)], step: F)
+fn dijkstra<F>(scores: &mut Scores, starts: &[(Point, Direction)], costs: Costs, step: F)
 where
     F: Fn(Direction, Point) -> Point,
 {
@@ -94,16 +139,20 @@ where
 
     while let Some(state) = heap.pop() {
         let next_positions = [
-            State::new(state.point, state.direction.turn_left(), state.score + 1000),
+            State::new(
+                state.point,
+                state.direction.turn_left(),
+                state.score + costs.turn,
+            ),
             State::new(
                 state.point,
                 state.direction.turn_right(),
-                state.score + 1000,
+                state.score + costs.turn,
             ),
             State::new(
                 step(state.direction, state.point),
                 state.direction,
-                state.score + 1,
+                state.score + costs.step,
             ),
         ];
 
@@ -123,24 +172,33 @@ where
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+#[derive(Clone)]
+struct Maze {
+    walls: [[bool; SIZE]; SIZE],
+    start: Point,
+    end: Point,
+    width: usize,
+    height: usize,
+}
+
+fn parse<T>(lines: Lines<T>) -> Maze
 where
     T: BufRead,
 {
-    let mut scores = [[[usize::MAX; 4]; SIZE]; SIZE];
-    let mut scores2 = [[[usize::MAX; 4]; SIZE]; SIZE];
+    let mut walls = [[false; SIZE]; SIZE];
     let mut start = (0, 0);
     let mut end = (0, 0);
+    let (mut width, mut height) = (0, 0);
 
     for (y, line) in lines.enumerate() {
         let line = line.unwrap();
 
+        width = width.max(line.len());
+        height = y + 1;
+
         for (x, c) in line.as_bytes().iter().enumerate() {
             match c {
-                b'#' => {
-                    scores[y][x] = [0, 0, 0, 0];
-                    scores2[y][x] = [0, 0, 0, 0]
-                }
+                b'#' => walls[y][x] = true,
                 b'S' => start = (x as u16, y as u16),
                 b'E' => end = (x as u16, y as u16),
                 b'.' => (),
@@ -149,40 +207,336 @@ where
         }
     }
 
-    dijkstra(&mut scores, &[(start, Direction::Right)], |d, p| {
-        d.forward(p)
-    });
+    Maze {
+        walls,
+        start,
+        end,
+        width,
+        height,
+    }
+}
+
+fn new_scores(maze: &Maze) -> Scores {
+    let mut scores = vec![[[usize::MAX; 4]; SIZE]; SIZE];
+
+    for (y, line) in maze.walls.iter().enumerate() {
+        for (x, &wall) in line.iter().enumerate() {
+            if wall {
+                scores[y][x] = [0, 0, 0, 0];
+            }
+        }
+    }
+
+    scores
+}
+
+fn forward_scores(maze: &Maze, costs: Costs) -> (Scores, usize) {
+    let mut scores = new_scores(maze);
 
-    let p1 = (0..4)
-        .map(|d| scores[end.1 as usize][end.0 as usize][d as usize])
+    dijkstra(
+        &mut scores,
+        &[(maze.start, Direction::Right)],
+        costs,
+        |d, p| d.forward(p),
+    );
+
+    let best = (0..4)
+        .map(|d| scores[maze.end.1 as usize][maze.end.0 as usize][d as usize])
         .min()
         .unwrap();
 
-    let ends = [
-        (end, Direction::Up),
-        (end, Direction::Right),
-        (end, Direction::Down),
-        (end, Direction::Left),
-    ];
-    dijkstra(&mut scores2, &ends, |d, p| d.backward(p));
+    (scores, best)
+}
 
-    let mut p2 = 0;
+// The score left to reach the end from each tile and direction
+fn backward_scores(maze: &Maze, costs: Costs) -> Scores {
+    let mut scores = new_scores(maze);
+    let ends = DIRECTIONS.map(|d| (maze.end, d));
+
+    dijkstra(&mut scores, &ends, costs, |d, p| d.backward(p));
+    scores
+}
+
+// The tiles on at least one of the best paths
+fn best_tiles(maze: &Maze, costs: Costs) -> Vec<Point> {
+    let (scores, best) = forward_scores(maze, costs);
+    let scores2 = backward_scores(maze, costs);
+    let mut tiles = vec![];
 
     for y in 0..SIZE {
         for x in 0..SIZE {
-            if scores[y][x][0] == 0 {
+            if maze.walls[y][x] {
                 continue;
             }
             for d in 0..4 {
-                if scores[y][x][d].overflowing_add(scores2[y][x][d]).0 == p1 {
-                    p2 += 1;
+                if scores[y][x][d].overflowing_add(scores2[y][x][d]).0 == best {
+                    tiles.push((x as u16, y as u16));
                     break;
                 }
             }
         }
     }
 
-    (p1, p2)
+    tiles
+}
+
+/// A way from the start to the end, as the direction of each step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Path {
+    score: usize,
+    steps: Vec<Direction>,
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.steps.iter().try_for_each(|step| step.fmt(f))
+    }
+}
+
+// A step of a path being searched, linked to the one before
+struct Node {
+    parent: Option<usize>,
+    point: Point,
+    direction: Direction,
+    score: usize,
+}
+
+// The maze with its dead ends walled up, as a path that never comes back to
+// a tile cannot go in and out of them
+fn fill_dead_ends(maze: &Maze) -> Maze {
+    let mut maze = maze.clone();
+    let open = |maze: &Maze, (x, y): Point| !maze.walls[y as usize][x as usize];
+    let mut dead_ends: Vec<Point> = vec![];
+
+    for y in 1..maze.height.saturating_sub(1) {
+        for x in 1..maze.width.saturating_sub(1) {
+            dead_ends.push((x as u16, y as u16));
+        }
+    }
+
+    while let Some(point) = dead_ends.pop() {
+        if !open(&maze, point) || point == maze.start || point == maze.end {
+            continue;
+        }
+
+        let exits: Vec<Point> = DIRECTIONS
+            .iter()
+            .map(|d| d.forward(point))
+            .filter(|&next| open(&maze, next))
+            .collect();
+
+        if exits.len() <= 1 {
+            maze.walls[point.1 as usize][point.0 as usize] = true;
+            dead_ends.extend(exits);
+        }
+    }
+
+    maze
+}
+
+// Best first search of the paths scoring at most `max_score`, the cheapest
+// first, found one at a time as there may be a great many of them. A path
+// never comes back to a tile, which would only add turns. The backward
+// scores ignore that, they only bound the score left, so some paths being
+// searched come to a dead end
+struct Search {
+    maze: Maze,
+    costs: Costs,
+    max_score: usize,
+    to_end: Scores,
+    nodes: Vec<Node>,
+    heap: BinaryHeap<(Reverse<usize>, usize)>,
+}
+
+impl Search {
+    fn new(maze: &Maze, costs: Costs, max_score: usize) -> Self {
+        let maze = fill_dead_ends(maze);
+        let to_end = backward_scores(&maze, costs);
+        let estimate =
+            to_end[maze.start.1 as usize][maze.start.0 as usize][Direction::Right as usize];
+
+        Search {
+            nodes: vec![Node {
+                parent: None,
+                point: maze.start,
+                direction: Direction::Right,
+                score: 0,
+            }],
+            heap: BinaryHeap::from([(Reverse(estimate), 0)]),
+            maze,
+            costs,
+            max_score,
+            to_end,
+        }
+    }
+
+    fn left(&self, (x, y): Point, d: Direction) -> usize {
+        self.to_end[y as usize][x as usize][d as usize]
+    }
+
+    // The steps from the start to a node
+    fn path(&self, i: usize) -> Path {
+        let mut steps = vec![];
+        let mut node = i;
+
+        while let Some(parent) = self.nodes[node].parent {
+            steps.push(self.nodes[node].direction);
+            node = parent;
+        }
+
+        steps.reverse();
+        Path {
+            score: self.nodes[i].score,
+            steps,
+        }
+    }
+}
+
+impl Iterator for Search {
+    type Item = Path;
+
+    fn next(&mut self) -> Option<Path> {
+        while let Some((Reverse(estimate), i)) = self.heap.pop() {
+            if estimate > self.max_score {
+                self.heap.clear();
+                break;
+            }
+
+            let Node {
+                point, direction, ..
+            } = self.nodes[i];
+
+            if point == self.maze.end {
+                return Some(self.path(i));
+            }
+
+            for d in DIRECTIONS {
+                let next = d.forward(point);
+
+                if self.maze.walls[next.1 as usize][next.0 as usize]
+                    || self.left(next, d) == usize::MAX
+                {
+                    continue;
+                }
+
+                let mut node = Some(i);
+
+                while let Some(n) = node {
+                    if self.nodes[n].point == next {
+                        break;
+                    }
+
+                    node = self.nodes[n].parent;
+                }
+
+                if node.is_some() {
+                    continue;
+                }
+
+                let score =
+                    self.nodes[i].score + direction.turns(d) * self.costs.turn + self.costs.step;
+
+                self.nodes.push(Node {
+                    parent: Some(i),
+                    point: next,
+                    direction: d,
+                    score,
+                });
+                self.heap
+                    .push((Reverse(score + self.left(next, d)), self.nodes.len() - 1));
+            }
+        }
+
+        None
+    }
+}
+
+// All the best paths
+fn best_paths(maze: &Maze, costs: Costs) -> Search {
+    let (_, best) = forward_scores(maze, costs);
+
+    Search::new(maze, costs, best)
+}
+
+// The paths from the cheapest, best or not
+fn k_shortest(maze: &Maze, costs: Costs) -> Search {
+    Search::new(maze, costs, usize::MAX)
+}
+
+// The maze with the tiles marked by O
+fn render(maze: &Maze, tiles: &[Point]) -> String {
+    let mut lines: Vec<Vec<u8>> = (0..maze.height)
+        .map(|y| {
+            (0..maze.width)
+                .map(|x| if maze.walls[y][x] { b'#' } else { b'.' })
+                .collect()
+        })
+        .collect();
+
+    for &(x, y) in tiles {
+        lines[y as usize][x as usize] = b'O';
+    }
+
+    lines[maze.start.1 as usize][maze.start.0 as usize] = b'S';
+    lines[maze.end.1 as usize][maze.end.0 as usize] = b'E';
+    lines
+        .into_iter()
+        .map(|line| String::from_utf8(line).unwrap() + "\n")
+        .collect()
+}
+
+fn part1(maze: &Maze) -> usize {
+    forward_scores(maze, Costs::PUZZLE).1
+}
+
+fn part2(maze: &Maze) -> usize {
+    best_tiles(maze, Costs::PUZZLE).len()
+}
+
+/// Prints the maze with the tiles of the best paths, up to `listed` of
+/// these paths as the direction of each step, then the `alternatives`
+/// cheapest paths.
+pub fn paths<T, W>(
+    lines: Lines<T>,
+    costs: Costs,
+    listed: usize,
+    alternatives: usize,
+    mut out: W,
+) -> io::Result<()>
+where
+    T: BufRead,
+    W: io::Write,
+{
+    let maze = parse(lines);
+    let best: Vec<Path> = best_paths(&maze, costs).take(listed + 1).collect();
+    let Some(score) = best.first().map(|path| path.score) else {
+        return Err(io::Error::other("the end cannot be reached"));
+    };
+
+    write!(out, "{}", render(&maze, &best_tiles(&maze, costs)))?;
+
+    if best.len() > listed {
+        writeln!(
+            out,
+            "more than {listed} best paths scoring {score}, the first {listed}:"
+        )?;
+    } else {
+        writeln!(out, "{} best paths scoring {score}:", best.len())?;
+    }
+
+    for path in best.iter().take(listed) {
+        writeln!(out, "{path}")?;
+    }
+
+    if alternatives > 0 {
+        writeln!(out, "{alternatives} cheapest paths:")?;
+
+        for path in k_shortest(&maze, costs).take(alternatives) {
+            writeln!(out, "{} {path}", path.score)?;
+        }
+    }
+
+    Ok(())
 }
 
 #[test]
@@ -221,16 +575,141 @@ fn check() {
 #################";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST1).lines()), (7036, 45));
-    assert_eq!(resolve(Cursor::new(TEST2).lines()), (11048, 64));
+    let input = parse(Cursor::new(TEST1).lines());
+
+    assert_eq!((part1(&input), part2(&input)), (7036, 45));
+
+    let input = parse(Cursor::new(TEST2).lines());
+
+    assert_eq!((part1(&input), part2(&input)), (11048, 64));
 }
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+#[test]
+fn check_paths() {
+    const TEST: &str = "#######
+#....E#
+#.#.#.#
+#S....#
+#######";
+    use std::io::Cursor;
+
+    let maze = parse(Cursor::new(TEST).lines());
+    let best: Vec<Path> = best_paths(&maze, Costs::PUZZLE).collect();
+    let steps: Vec<String> = best.iter().map(|path| path.to_string()).collect();
+
+    assert_eq!(steps, [">>>>^^"]);
+    assert_eq!(best[0].score, 1006);
+
+    let paths: Vec<Path> = k_shortest(&maze, Costs::PUZZLE).take(3).collect();
+    let scores: Vec<usize> = paths.iter().map(|path| path.score).collect();
+
+    assert_eq!(scores, [1006, 2006, 2006]);
+    assert_eq!(
+        render(&maze, &best_tiles(&maze, Costs::PUZZLE)),
+        "#######\n#....E#\n#.#.#O#\n#SOOOO#\n#######\n"
+    );
+
+    // With free turns, any path of 6 steps is one of the best
+    let costs = Costs { turn: 0, step: 1 };
+
+    assert_eq!(best_paths(&maze, costs).count(), 3);
+
+    let mut out = vec![];
+
+    self::paths(Cursor::new(TEST).lines(), costs, 2, 1, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "#######\n#OOOOE#\n#O#O#O#\n#SOOOO#\n#######\n\
+         more than 2 best paths scoring 6, the first 2:\n\
+         >>>>^^\n>>^^>>\n\
+         1 cheapest paths:\n\
+         6 >>>>^^\n"
+    );
+    assert_eq!(best_tiles(&maze, costs).len(), 13);
+}
+
+// Every path that never comes back to a tile, from a depth first search
+#[cfg(test)]
+fn all_paths(maze: &Maze, costs: Costs) -> Vec<Path> {
+    fn visit(
+        maze: &Maze,
+        costs: Costs,
+        tiles: &mut Vec<Point>,
+        path: &mut Path,
+        paths: &mut Vec<Path>,
+    ) {
+        let point = *tiles.last().unwrap();
+
+        if point == maze.end {
+            paths.push(path.clone());
+            return;
+        }
+
+        let direction = path.steps.last().copied().unwrap_or(Direction::Right);
+
+        for d in DIRECTIONS {
+            let next = d.forward(point);
+
+            if maze.walls[next.1 as usize][next.0 as usize] || tiles.contains(&next) {
+                continue;
+            }
+
+            let score = direction.turns(d) * costs.turn + costs.step;
+
+            tiles.push(next);
+            path.steps.push(d);
+            path.score += score;
+            visit(maze, costs, tiles, path, paths);
+            path.score -= score;
+            path.steps.pop();
+            tiles.pop();
+        }
+    }
+
+    let mut paths = vec![];
+    let mut path = Path {
+        score: 0,
+        steps: vec![],
+    };
+
+    visit(maze, costs, &mut vec![maze.start], &mut path, &mut paths);
+    paths
+}
+
+#[test]
+fn check_k_shortest() {
+    // Limiting the visits of each tile and direction loses the tenth path
+    // with these costs: the cheap ways there come to a dead end
+    const TEST: &str = "#########
+###....E#
+#..#.####
+#......##
+#.#....##
+#S#.#...#
+#########";
+    use std::io::Cursor;
+
+    let maze = parse(Cursor::new(TEST).lines());
+
+    for costs in [Costs { turn: 1, step: 1 }, Costs::PUZZLE] {
+        let mut expected = all_paths(&maze, costs);
+        let key = |path: &Path| (path.score, path.to_string());
+
+        expected.sort_by_key(key);
+
+        let mut found: Vec<Path> = k_shortest(&maze, costs).collect();
+
+        // Paths of the same score may come in any order
+        found.sort_by_key(key);
+        assert_eq!(found, expected);
+
+        let scores = |paths: &[Path]| paths.iter().map(|path| path.score).collect::<Vec<_>>();
+
+        assert_eq!(
+            scores(&k_shortest(&maze, costs).take(10).collect::<Vec<_>>()),
+            scores(&expected[..10])
+        );
+    }
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,6 +1,8 @@
 use regex::Regex;
+use std::collections::BTreeSet;
 use std::fmt::{Display, Formatter, Result, Write};
-use std::io::{BufRead, Lines};
+use std::io::{self, BufRead, Lines};
+use std::str::FromStr;
 
 struct Cpu {
     a: u64,
@@ -24,8 +26,15 @@ impl Cpu {
         }
     }
 
+    // Shifting by 64 or more leaves nothing
+    fn divide(&self, operand: u8) -> u64 {
+        let shift = u32::try_from(self.combo(operand)).unwrap_or(u32::MAX);
+
+        self.a.checked_shr(shift).unwrap_or(0)
+    }
+
     fn adv(&mut self, operand: u8) {
-        self.a >>= self.combo(operand);
+        self.a = self.divide(operand);
     }
 
     fn bxl(&mut self, operand: u8) {
@@ -51,11 +60,11 @@ impl Cpu {
     }
 
     fn bdv(&mut self, operand: u8) {
-        self.b = self.a >> self.combo(operand);
+        self.b = self.divide(operand);
     }
 
     fn cdv(&mut self, operand: u8) {
-        self.c = self.a >> self.combo(operand);
+        self.c = self.divide(operand);
     }
 }
 
@@ -71,31 +80,125 @@ impl Display for Cpu {
 
 struct Program(Vec<u8>);
 
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+// bxl and jnz take a literal operand, bxc ignores it but it is kept so that
+// the program assembles back to the same bytes
+fn is_combo(opcode: u8) -> bool {
+    matches!(opcode, 0 | 2 | 5 | 6 | 7)
+}
+
+// Registers of the combo operands are written a, b and c, 7 is reserved
+fn write_operand(f: &mut Formatter, opcode: u8, operand: u8) -> Result {
+    match operand {
+        4..7 if is_combo(opcode) => write!(f, "{}", (b'a' + operand - 4) as char),
+        _ => write!(f, "{operand}"),
+    }
+}
+
+// One instruction per line
+impl Display for Program {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        for instruction in self.0.chunks(2) {
+            write!(f, "{}", MNEMONICS[instruction[0] as usize])?;
+
+            if let Some(&operand) = instruction.get(1) {
+                write!(f, " ")?;
+                write_operand(f, instruction[0], operand)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Reads the text given by Display back, ignoring blank lines and comments
+// starting with ';'
+impl FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut program = vec![];
+
+        for (n, line) in (1..).zip(s.lines()) {
+            let line = line.split(';').next().unwrap().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (mnemonic, operand) = line.split_once(' ').unwrap_or((line, ""));
+            let operand = operand.trim();
+            let opcode = MNEMONICS
+                .iter()
+                .position(|&m| m == mnemonic)
+                .ok_or(format!("line {n}: unknown instruction {mnemonic}"))?
+                as u8;
+            let operand = match (operand, operand.parse::<u8>()) {
+                ("a" | "b" | "c", _) if is_combo(opcode) => operand.as_bytes()[0] - b'a' + 4,
+                (_, Ok(4..7)) if is_combo(opcode) => {
+                    return Err(format!("line {n}: registers are written a, b and c"));
+                }
+                (_, Ok(7)) if is_combo(opcode) => {
+                    return Err(format!("line {n}: combo operand 7 is reserved"));
+                }
+                (_, Ok(value @ 0..8)) => value,
+                _ => return Err(format!("line {n}: bad operand \"{operand}\"")),
+            };
+
+            program.extend([opcode, operand]);
+        }
+
+        Ok(Program(program))
+    }
+}
+
 struct ProgramIterator<'a> {
     program: &'a Program,
     cpu: Cpu,
 }
 
+impl<'a> ProgramIterator<'a> {
+    // Past the last instruction, or on the last byte without its operand
+    fn halted(&self) -> bool {
+        self.cpu
+            .pc
+            .checked_add(1)
+            .is_none_or(|next| next >= self.program.0.len())
+    }
+
+    // Executes one instruction and gives its output, None once halted
+    fn step(&mut self) -> Option<Option<u8>> {
+        let opcode = *self.program.0.get(self.cpu.pc)?;
+        let operand = *self.program.0.get(self.cpu.pc.checked_add(1)?)?;
+
+        self.cpu.pc += 2;
+
+        match opcode {
+            0 => self.cpu.adv(operand),
+            1 => self.cpu.bxl(operand),
+            2 => self.cpu.bst(operand),
+            3 => self.cpu.jnz(operand),
+            4 => self.cpu.bxc(),
+            5 => return Some(Some(self.cpu.out(operand))),
+            6 => self.cpu.bdv(operand),
+            7 => self.cpu.cdv(operand),
+            _ => panic!(),
+        }
+
+        Some(None)
+    }
+}
+
 impl<'a> Iterator for ProgramIterator<'a> {
     type Item = u8;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.cpu.pc < self.program.0.len() {
-            let opcode = self.program.0[self.cpu.pc];
-            let operand = self.program.0[self.cpu.pc + 1];
-
-            self.cpu.pc += 2;
-
-            match opcode {
-                0 => self.cpu.adv(operand),
-                1 => self.cpu.bxl(operand),
-                2 => self.cpu.bst(operand),
-                3 => self.cpu.jnz(operand),
-                4 => self.cpu.bxc(),
-                5 => return Some(self.cpu.out(operand)),
-                6 => self.cpu.bdv(operand),
-                7 => self.cpu.cdv(operand),
-                _ => panic!(),
+        while let Some(output) = self.step() {
+            if output.is_some() {
+                return output;
             }
         }
 
@@ -134,10 +237,39 @@ fn check_program() {
     }
 }
 
-fn check_first_output(program: &Program, a: u64, expected: u8) -> Option<u64> {
-    let output = program.run(a, 0, 0).next().unwrap();
+#[test]
+fn check_assembler() {
+    // The examples and a real input
+    let programs = [
+        vec![0, 1, 5, 4, 3, 0],
+        vec![0, 3, 5, 4, 3, 0],
+        vec![2, 4, 1, 2, 7, 5, 4, 5, 1, 3, 5, 5, 0, 3, 3, 0],
+    ];
+
+    for bytes in programs {
+        let text = Program(bytes.clone()).to_string();
+
+        assert_eq!(text.parse::<Program>().unwrap().0, bytes);
+    }
+
+    assert_eq!(
+        Program(vec![2, 4, 1, 2, 7, 5, 4, 5, 1, 3, 5, 5, 0, 3, 3, 0]).to_string(),
+        "bst a\nbxl 2\ncdv b\nbxc 5\nbxl 3\nout b\nadv 3\njnz 0\n"
+    );
+
+    let program: Program = "adv 3 ; A /= 8\n\nout a\njnz 0\n".parse().unwrap();
 
-    if output == expected {
+    assert_eq!(program.0, [0, 3, 5, 4, 3, 0]);
+    assert_eq!(get_a(&program), Ok(117440));
+    assert!("out 4".parse::<Program>().is_err());
+    assert!("jmp 0".parse::<Program>().is_err());
+    assert!("bxl 8".parse::<Program>().is_err());
+    assert!("out 7".parse::<Program>().is_err());
+    assert_eq!("bxl 7".parse::<Program>().unwrap().0, [1, 7]);
+}
+
+fn check_first_output(program: &Program, a: u64, expected: u8) -> Option<u64> {
+    if program.run(a, 0, 0).next() == Some(expected) {
         Some(a)
     } else {
         None
@@ -163,14 +295,384 @@ fn find_values(n: usize, program: &Program) -> Vec<u64> {
     }
 }
 
-fn get_a(program: &Program) -> u64 {
-    let vs = find_values(program.0.len(), program);
+fn get_a(program: &Program) -> std::result::Result<u64, String> {
+    if program.0.is_empty() {
+        return Ok(0);
+    }
+
+    find_values(program.0.len(), program)
+        .first()
+        .copied()
+        .ok_or_else(|| {
+            let program: Vec<String> = program.0.iter().map(|o| o.to_string()).collect();
 
-    if vs.is_empty() {
-        u64::MAX
-    } else {
-        vs[0]
+            format!("no value of A outputs {}", program.join(","))
+        })
+}
+
+// Tries every value of A, only usable on small programs like the example:
+// on a real input it has to be stopped with --timeout
+fn get_a_brute_force(program: &Program) -> std::result::Result<u64, String> {
+    (0..=u64::MAX)
+        .find(|&a| program.run(a, 0, 0).eq(program.0.iter().copied()))
+        .ok_or_else(|| String::from("no value of A outputs the program"))
+}
+
+// The general solver executes the program on an unknown A. Each bit of the
+// registers is then an xor of bits of A and of a constant, and the program
+// can only go on once the bits it needs are known: the output must match the
+// target, and the jumps and the shifts by a register take each possible
+// value in turn. This builds a system of linear equations over the bits of
+// A for each path through the program.
+const MAX_STEPS: usize = 100_000;
+
+// The xor of the bits of A in the mask and of the constant
+#[derive(Clone, Copy, Default)]
+struct Form {
+    mask: u64,
+    constant: bool,
+}
+
+impl Form {
+    fn constant(constant: bool) -> Self {
+        Form { mask: 0, constant }
+    }
+}
+
+// Bit i of a register is its element i
+type Register = [Form; 64];
+
+fn constant_register(value: u64) -> Register {
+    std::array::from_fn(|i| Form::constant(value >> i & 1 == 1))
+}
+
+// Equations form = 0 in echelon form, keyed by their highest bit of A
+#[derive(Clone)]
+struct Equations([Form; 64]);
+
+impl Equations {
+    // What is left of the form once the known bits are replaced
+    fn reduce(&self, mut form: Form) -> Form {
+        for bit in (0..64).rev() {
+            if form.mask >> bit & 1 == 1 && self.0[bit].mask != 0 {
+                form.mask ^= self.0[bit].mask;
+                form.constant ^= self.0[bit].constant;
+            }
+        }
+
+        form
+    }
+
+    fn value(&self, form: Form) -> Option<bool> {
+        let form = self.reduce(form);
+
+        (form.mask == 0).then_some(form.constant)
+    }
+
+    fn register_value(&self, register: &Register) -> Option<u64> {
+        (0..64).try_fold(0, |value, bit| {
+            self.value(register[bit]).map(|b| value | (b as u64) << bit)
+        })
+    }
+
+    // Adds form = value, false when it contradicts the other equations
+    fn add(&mut self, form: Form, value: bool) -> bool {
+        let form = self.reduce(Form {
+            mask: form.mask,
+            constant: form.constant ^ value,
+        });
+
+        if form.mask == 0 {
+            return !form.constant;
+        }
+
+        self.0[63 - form.mask.leading_zeros() as usize] = form;
+        true
     }
+
+    // Each way to give values to the forms, as the value of the forms in
+    // order and the equations that follow
+    fn assignments(&self, forms: &[Form]) -> Vec<(u64, Equations)> {
+        let Some((&last, forms)) = forms.split_last() else {
+            return vec![(0, self.clone())];
+        };
+
+        self.assignments(forms)
+            .into_iter()
+            .flat_map(|(value, equations)| {
+                [false, true].into_iter().filter_map(move |b| {
+                    let mut equations = equations.clone();
+
+                    equations
+                        .add(last, b)
+                        .then(|| (value | (b as u64) << forms.len(), equations))
+                })
+            })
+            .collect()
+    }
+}
+
+// The smallest A solving the equations with none of the registers zero
+fn smallest(equations: Equations, nonzero: &[Register], bit: usize) -> Option<u64> {
+    if nonzero
+        .iter()
+        .any(|register| register.iter().all(|&f| equations.value(f) == Some(false)))
+    {
+        return None;
+    }
+
+    if bit == 0 {
+        return equations.register_value(&std::array::from_fn(|i| Form {
+            mask: 1 << i,
+            constant: false,
+        }));
+    }
+
+    let a = Form {
+        mask: 1 << (bit - 1),
+        constant: false,
+    };
+
+    [false, true].into_iter().find_map(|b| {
+        let mut equations = equations.clone();
+
+        if equations.add(a, b) {
+            smallest(equations, nonzero, bit - 1)
+        } else {
+            None
+        }
+    })
+}
+
+#[derive(Clone)]
+struct Path {
+    registers: [Register; 3],
+    pc: usize,
+    outputs: usize,
+    steps: usize,
+    equations: Equations,
+    nonzero: Vec<Register>,
+}
+
+struct Search<'a> {
+    program: &'a [u8],
+    target: &'a [u8],
+    best: Option<u64>,
+    limited: bool,
+}
+
+impl<'a> Search<'a> {
+    fn combo(path: &Path, operand: u8) -> std::result::Result<Register, String> {
+        match operand {
+            0..4 => Ok(constant_register(operand as u64)),
+            4..7 => Ok(path.registers[operand as usize - 4]),
+            _ => Err(format!("reserved combo operand 7 at {}", path.pc - 2)),
+        }
+    }
+
+    // A shifted by each possible value of the operand, at least 64 giving 0
+    fn shifts(path: &Path, operand: u8) -> std::result::Result<Vec<(Register, Path)>, String> {
+        let amount = Search::combo(path, operand)?;
+        let a = path.registers[0];
+        let mut below_64 = path.equations.clone();
+        let mut shifts = vec![];
+
+        if amount[6..].iter().all(|&f| below_64.add(f, false)) {
+            for (shift, equations) in below_64.assignments(&amount[..6]) {
+                let mut path = path.clone();
+
+                path.equations = equations;
+                shifts.push((
+                    std::array::from_fn(|i| a.get(i + shift as usize).copied().unwrap_or_default()),
+                    path,
+                ));
+            }
+        }
+
+        if !amount[6..]
+            .iter()
+            .all(|&f| path.equations.value(f) == Some(false))
+        {
+            let mut path = path.clone();
+
+            path.nonzero.push(std::array::from_fn(|i| match i {
+                6.. => amount[i],
+                _ => Form::default(),
+            }));
+            shifts.push(([Form::default(); 64], path));
+        }
+
+        Ok(shifts)
+    }
+
+    // Runs the path until it ends or branches, the branches being left to
+    // run in `paths`
+    fn explore(
+        &mut self,
+        mut path: Path,
+        paths: &mut Vec<Path>,
+    ) -> std::result::Result<(), String> {
+        loop {
+            if path.pc + 1 >= self.program.len() {
+                if path.outputs == self.target.len() {
+                    if let Some(a) = smallest(path.equations, &path.nonzero, 64) {
+                        self.best = Some(self.best.map_or(a, |best| best.min(a)));
+                    }
+                }
+
+                return Ok(());
+            }
+
+            if path.steps == MAX_STEPS {
+                self.limited = true;
+                return Ok(());
+            }
+
+            let opcode = self.program[path.pc];
+            let operand = self.program[path.pc + 1];
+
+            path.pc += 2;
+            path.steps += 1;
+
+            match opcode {
+                0 | 6 | 7 => {
+                    for (shifted, mut path) in Search::shifts(&path, operand)? {
+                        path.registers[if opcode == 0 { 0 } else { opcode as usize - 5 }] = shifted;
+                        paths.push(path);
+                    }
+
+                    return Ok(());
+                }
+                1 => {
+                    for (bit, form) in path.registers[1][..3].iter_mut().enumerate() {
+                        form.constant ^= operand >> bit & 1 == 1;
+                    }
+                }
+                2 => {
+                    let value = Search::combo(&path, operand)?;
+
+                    path.registers[1] = std::array::from_fn(|i| match i {
+                        0..3 => value[i],
+                        _ => Form::default(),
+                    });
+                }
+                3 => {
+                    let a = path.registers[0];
+                    let mut zero = path.clone();
+
+                    if a.iter().all(|&f| zero.equations.add(f, false)) {
+                        paths.push(zero);
+                    }
+
+                    path.pc = operand as usize;
+                    path.nonzero.push(a);
+
+                    if !a.iter().all(|&f| path.equations.value(f) == Some(false)) {
+                        paths.push(path);
+                    }
+
+                    return Ok(());
+                }
+                4 => {
+                    for bit in 0..64 {
+                        let c = path.registers[2][bit];
+                        let b = &mut path.registers[1][bit];
+
+                        b.mask ^= c.mask;
+                        b.constant ^= c.constant;
+                    }
+                }
+                5 => {
+                    let value = Search::combo(&path, operand)?;
+                    let Some(&expected) = self.target.get(path.outputs) else {
+                        return Ok(());
+                    };
+
+                    if !(0..3).all(|bit| path.equations.add(value[bit], expected >> bit & 1 == 1)) {
+                        return Ok(());
+                    }
+
+                    path.outputs += 1;
+                }
+                _ => return Err(format!("invalid opcode {opcode} at {}", path.pc - 2)),
+            }
+        }
+    }
+}
+
+/// The smallest A making the program output the target with the given B
+/// and C, whatever the program does.
+fn solve(program: &Program, target: &[u8], b: u64, c: u64) -> std::result::Result<u64, String> {
+    let mut search = Search {
+        program: &program.0,
+        target,
+        best: None,
+        limited: false,
+    };
+
+    let mut paths = vec![Path {
+        registers: [
+            std::array::from_fn(|i| Form {
+                mask: 1 << i,
+                constant: false,
+            }),
+            constant_register(b),
+            constant_register(c),
+        ],
+        pc: 0,
+        outputs: 0,
+        steps: 0,
+        equations: Equations([Form::default(); 64]),
+        nonzero: vec![],
+    }];
+
+    while let Some(path) = paths.pop() {
+        search.explore(path, &mut paths)?;
+    }
+
+    let target: Vec<String> = target.iter().map(|o| o.to_string()).collect();
+
+    match (search.best, search.limited) {
+        (Some(a), _) => Ok(a),
+        (None, false) => Err(format!("no value of A outputs {}", target.join(","))),
+        (None, true) => Err(format!(
+            "no value of A found to output {} within {MAX_STEPS} steps",
+            target.join(",")
+        )),
+    }
+}
+
+#[test]
+fn check_solver() {
+    let example = Program(vec![0, 1, 5, 4, 3, 0]);
+
+    // The lowest bit of 729 is shifted out before any output
+    assert_eq!(
+        solve(&example, &[4, 6, 3, 5, 6, 3, 5, 2, 1, 0], 0, 0),
+        Ok(728)
+    );
+
+    let real = Program(vec![2, 4, 1, 2, 7, 5, 4, 5, 1, 3, 5, 5, 0, 3, 3, 0]);
+
+    assert_eq!(solve(&real, &real.0, 0, 0), get_a(&real));
+
+    // Two bits of A per output, which get_a does not handle
+    let program: Program = "bst a\nout b\nadv 2\njnz 0".parse().unwrap();
+    let target: Vec<u8> = program.run(12345, 0, 0).collect();
+    let a = solve(&program, &target, 0, 0).unwrap();
+
+    assert_eq!(
+        Some(a),
+        (0..).find(|&a| program.run(a, 0, 0).eq(target.iter().copied()))
+    );
+
+    // The outputs overlap by a bit, which the first two disagree on
+    let program: Program = "bst a\nbxl 1\nout b\nadv 2\njnz 0".parse().unwrap();
+
+    assert_eq!(
+        solve(&program, &program.0, 0, 0),
+        Err(String::from("no value of A outputs 2,4,1,1,5,5,0,2,3,0"))
+    );
 }
 
 fn get_output(program: &Program, a: u64, b: u64, c: u64) -> String {
@@ -187,7 +689,13 @@ fn get_output(program: &Program, a: u64, b: u64, c: u64) -> String {
     output
 }
 
-fn resolve<T>(lines: Lines<T>) -> (String, u64)
+struct Computer {
+    registers: [u64; 3],
+    program: Program,
+}
+
+// The program is made of 3-bit numbers, one opcode then one operand
+fn parse<T>(lines: Lines<T>) -> std::result::Result<Computer, String>
 where
     T: BufRead,
 {
@@ -202,28 +710,399 @@ where
             continue;
         }
 
-        let caps = re.captures(&line).unwrap();
+        let caps = re.captures(&line).ok_or(format!("bad line \"{line}\""))?;
 
         if let Some(values) = caps.get(3) {
             program = values
                 .as_str()
                 .split(',')
-                .map(|s| s.parse::<u8>().unwrap())
-                .collect();
+                .map(|s| match s.parse::<u8>() {
+                    Ok(value @ 0..8) => Ok(value),
+                    _ => Err(format!("bad program value {s}, they go from 0 to 7")),
+                })
+                .collect::<std::result::Result<_, _>>()?;
         } else {
-            let register = caps.get(1).unwrap().as_str().as_bytes()[0] - b'A';
-            let value = caps.get(2).unwrap().as_str().parse::<u64>().unwrap();
+            let register = caps.get(1).unwrap().as_str().as_bytes()[0];
+            let value = caps.get(2).unwrap().as_str();
+            let value = value
+                .parse::<u64>()
+                .map_err(|_| format!("register value {value} is too large"))?;
 
-            registers[register as usize] = value;
+            match register {
+                b'A'..=b'C' => registers[(register - b'A') as usize] = value,
+                _ => return Err(format!("unknown register {}", register as char)),
+            }
         }
     }
 
-    let program = Program(program);
+    Ok(Computer {
+        registers,
+        program: Program(program),
+    })
+}
+
+fn part1(computer: &std::result::Result<Computer, String>) -> std::result::Result<String, String> {
+    let computer = computer.as_ref().map_err(Clone::clone)?;
+    let [a, b, c] = computer.registers;
+
+    Ok(get_output(&computer.program, a, b, c))
+}
 
-    (
-        get_output(&program, registers[0], registers[1], registers[2]),
-        get_a(&program),
+fn part2(computer: &std::result::Result<Computer, String>) -> std::result::Result<u64, String> {
+    let computer = computer.as_ref().map_err(Clone::clone)?;
+
+    get_a(&computer.program)
+}
+
+fn part2_brute_force(
+    computer: &std::result::Result<Computer, String>,
+) -> std::result::Result<u64, String> {
+    let computer = computer.as_ref().map_err(Clone::clone)?;
+
+    get_a_brute_force(&computer.program)
+}
+
+fn part2_symbolic(
+    computer: &std::result::Result<Computer, String>,
+) -> std::result::Result<u64, String> {
+    let computer = computer.as_ref().map_err(Clone::clone)?;
+    let [_, b, c] = computer.registers;
+
+    solve(&computer.program, &computer.program.0, b, c)
+}
+
+// The most instructions continue executes, so that a program that never
+// halts gives control back
+const MAX_CONTINUE: usize = 10_000_000;
+
+const HELP: &str = "step [n]         execute n instructions, 1 by default
+continue         run until a breakpoint or the end of the program, giving up
+                 after 10000000 instructions
+break <pc>       stop before the instruction at pc
+delete <pc>      remove the breakpoint at pc
+set <reg> <n>    change register a, b, c or pc
+print            show the registers and the output so far
+list             disassemble the program, > marking pc and * breakpoints
+quit";
+
+struct Debugger<'a> {
+    iterator: ProgramIterator<'a>,
+    breakpoints: BTreeSet<usize>,
+    output: Vec<u8>,
+}
+
+impl<'a> Debugger<'a> {
+    // Whether an instruction was executed, or why it could not be
+    fn step(&mut self) -> std::result::Result<bool, String> {
+        let pc = self.iterator.cpu.pc;
+        let program = &self.iterator.program.0;
+
+        if !self.iterator.halted() && is_combo(program[pc]) && program[pc + 1] == 7 {
+            return Err(format!("reserved combo operand 7 at {pc}\n"));
+        }
+
+        Ok(match self.iterator.step() {
+            Some(output) => {
+                self.output.extend(output);
+                true
+            }
+            None => false,
+        })
+    }
+
+    // Steps n times at most, stopping on the first error or breakpoint
+    fn run(&mut self, n: usize, breakpoints: bool) -> String {
+        for i in 0..n {
+            match self.step() {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(error) => return error + &self.state(),
+            }
+
+            if breakpoints && self.breakpoints.contains(&self.iterator.cpu.pc) {
+                break;
+            }
+
+            if breakpoints && i + 1 == n {
+                return format!("stopped after {n} instructions\n") + &self.state();
+            }
+        }
+
+        self.state()
+    }
+
+    fn state(&self) -> String {
+        let pc = self.iterator.cpu.pc;
+        let program = &self.iterator.program.0;
+        let next = if self.iterator.halted() {
+            String::from("halted")
+        } else {
+            Program(program[pc..pc + 2].to_vec()).to_string()
+        };
+        let output: Vec<String> = self.output.iter().map(|o| o.to_string()).collect();
+
+        format!(
+            "{}  {}\noutput: {}\n",
+            self.iterator,
+            next.trim_end(),
+            output.join(",")
+        )
+    }
+
+    fn list(&self) -> String {
+        let mut list = String::new();
+
+        for (pc, line) in (0..)
+            .step_by(2)
+            .zip(self.iterator.program.to_string().lines())
+        {
+            let marker = if pc == self.iterator.cpu.pc { '>' } else { ' ' };
+            let breakpoint = if self.breakpoints.contains(&pc) {
+                '*'
+            } else {
+                ' '
+            };
+
+            writeln!(list, "{marker}{breakpoint}{pc:>3}  {line}").unwrap();
+        }
+
+        list
+    }
+
+    // Gives what to print, or None to quit
+    fn command(&mut self, line: &str) -> Option<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |i: usize| words.get(i).and_then(|w| w.parse::<u64>().ok());
+
+        Some(match (words.first().copied().unwrap_or(""), words.len()) {
+            ("", _) => String::new(),
+            ("quit" | "q", _) => return None,
+            ("help" | "h", _) => format!("{HELP}\n"),
+            ("step" | "s", 1 | 2) => self.run(number(1).unwrap_or(1) as usize, false),
+            ("continue" | "c", 1) => self.run(MAX_CONTINUE, true),
+            ("break" | "b", 2) if number(1).is_some() => {
+                self.breakpoints.insert(number(1).unwrap() as usize);
+                String::new()
+            }
+            ("delete" | "d", 2) if number(1).is_some() => {
+                self.breakpoints.remove(&(number(1).unwrap() as usize));
+                String::new()
+            }
+            ("set", 3) if number(2).is_some() => {
+                let value = number(2).unwrap();
+                let cpu = &mut self.iterator.cpu;
+
+                match words[1] {
+                    "a" => cpu.a = value,
+                    "b" => cpu.b = value,
+                    "c" => cpu.c = value,
+                    "pc" => match usize::try_from(value) {
+                        Ok(pc) if pc < self.iterator.program.0.len() => cpu.pc = pc,
+                        _ => {
+                            return Some(format!(
+                                "pc {value} is outside of the program, which has {} bytes\n",
+                                self.iterator.program.0.len()
+                            ))
+                        }
+                    },
+                    register => return Some(format!("unknown register {register}\n")),
+                }
+                self.state()
+            }
+            ("print" | "p", 1) => self.state(),
+            ("list" | "l", 1) => self.list(),
+            _ => format!("bad command \"{line}\", see help\n"),
+        })
+    }
+}
+
+/// Steps through the program of day 17 given by its input or as assembly,
+/// reading one command per line from `commands` (see `help`).
+pub fn debug<T, R, W>(
+    input: Option<Lines<T>>,
+    asm: Option<&str>,
+    commands: R,
+    mut out: W,
+) -> io::Result<()>
+where
+    T: BufRead,
+    R: BufRead,
+    W: io::Write,
+{
+    let computer = computer(input, asm)?;
+    let [a, b, c] = computer.registers;
+    let mut debugger = Debugger {
+        iterator: computer.program.run(a, b, c),
+        breakpoints: BTreeSet::new(),
+        output: vec![],
+    };
+
+    write!(out, "{}", debugger.state())?;
+
+    for line in commands.lines() {
+        match debugger.command(&line?) {
+            Some(text) => write!(out, "{text}")?,
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+// The input, or zeroed registers, running the given assembly if any
+fn computer<T: BufRead>(input: Option<Lines<T>>, asm: Option<&str>) -> io::Result<Computer> {
+    let mut computer = match input {
+        Some(lines) => {
+            parse(lines).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+        }
+        None => Computer {
+            registers: [0; 3],
+            program: Program(vec![]),
+        },
+    };
+
+    if let Some(asm) = asm {
+        computer.program = asm
+            .parse()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    }
+
+    Ok(computer)
+}
+
+/// Writes the lowest value of A making the program of day 17, given by its
+/// input or as assembly, output `target` (comma separated values, the
+/// program itself if `None`) with the registers B and C of the input.
+pub fn find_a<T, W>(
+    input: Option<Lines<T>>,
+    asm: Option<&str>,
+    target: Option<&str>,
+    mut out: W,
+) -> io::Result<()>
+where
+    T: BufRead,
+    W: io::Write,
+{
+    let computer = computer(input, asm)?;
+    let target = match target {
+        Some(target) => target
+            .split(',')
+            .map(|o| match o.trim().parse::<u8>() {
+                Ok(o @ 0..8) => Ok(o),
+                _ => Err(format!("bad output \"{o}\", outputs go from 0 to 7")),
+            })
+            .collect::<std::result::Result<Vec<u8>, String>>(),
+        None => Ok(computer.program.0.clone()),
+    };
+    let [_, b, c] = computer.registers;
+
+    match target.and_then(|target| solve(&computer.program, &target, b, c)) {
+        Ok(a) => writeln!(out, "{a}"),
+        Err(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+    }
+}
+
+#[test]
+fn check_debugger() {
+    const TEST: &str = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+    const COMMANDS: &str = "break 4
+continue
+continue
+set a 1
+list
+step 2
+step
+jump 2
+quit
+step";
+    use std::io::Cursor;
+
+    let mut out = vec![];
+
+    debug(
+        Some(Cursor::new(TEST).lines()),
+        None,
+        Cursor::new(COMMANDS),
+        &mut out,
     )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "A: 729, B: 0, C: 0, PC: 0  adv 1
+output: 
+A: 364, B: 0, C: 0, PC: 4  jnz 0
+output: 4
+A: 182, B: 0, C: 0, PC: 4  jnz 0
+output: 4,6
+A: 1, B: 0, C: 0, PC: 4  jnz 0
+output: 4,6
+    0  adv 1
+    2  out a
+>*  4  jnz 0
+A: 0, B: 0, C: 0, PC: 2  out a
+output: 4,6
+A: 0, B: 0, C: 0, PC: 4  jnz 0
+output: 4,6,0
+bad command \"jump 2\", see help
+"
+    );
+}
+
+#[test]
+fn check_debugger_faults() {
+    use std::io::Cursor;
+
+    let run = |input: &str, commands: &str| {
+        let mut out = vec![];
+
+        debug(
+            Some(Cursor::new(input).lines()),
+            None,
+            Cursor::new(commands),
+            &mut out,
+        )
+        .unwrap();
+
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(
+        run("Register A: 5\nProgram: 6,5,5,7", "set b 64\nstep\nstep"),
+        "A: 5, B: 0, C: 0, PC: 0  bdv b
+output: 
+A: 5, B: 64, C: 0, PC: 0  bdv b
+output: 
+A: 5, B: 0, C: 0, PC: 2  out 7
+output: 
+reserved combo operand 7 at 2
+A: 5, B: 0, C: 0, PC: 2  out 7
+output: 
+"
+    );
+    assert!(run("Register A: 1\nProgram: 3,0", "continue")
+        .contains("stopped after 10000000 instructions\nA: 1, B: 0, C: 0, PC: 0  jnz 0\n"));
+    assert_eq!(
+        run(
+            "Register A: 1\nProgram: 0,1,5,4,3,0",
+            "set pc 18446744073709551615\nset pc 6\nset pc 5\nstep\nprint"
+        ),
+        "A: 1, B: 0, C: 0, PC: 0  adv 1
+output: 
+pc 18446744073709551615 is outside of the program, which has 6 bytes
+pc 6 is outside of the program, which has 6 bytes
+A: 1, B: 0, C: 0, PC: 5  halted
+output: 
+A: 1, B: 0, C: 0, PC: 5  halted
+output: 
+A: 1, B: 0, C: 0, PC: 5  halted
+output: 
+"
+    );
 }
 
 #[test]
@@ -240,16 +1119,50 @@ Register C: 0
 Program: 0,3,5,4,3,0";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST1).lines()).0, "4,6,3,5,6,3,5,2,1,0");
-    assert_eq!(resolve(Cursor::new(TEST2).lines()).1, 117440);
-}
+    assert_eq!(
+        part1(&parse(Cursor::new(TEST1).lines())),
+        Ok(String::from("4,6,3,5,6,3,5,2,1,0"))
+    );
+    assert_eq!(part2(&parse(Cursor::new(TEST2).lines())), Ok(117440));
+    assert_eq!(
+        part2(&parse(Cursor::new("Program: 5,1").lines())),
+        Err(String::from("no value of A outputs 5,1"))
+    );
+    assert_eq!(
+        part2_brute_force(&parse(Cursor::new(TEST2).lines())),
+        Ok(117440)
+    );
+    assert_eq!(
+        part2_symbolic(&parse(Cursor::new(TEST2).lines())),
+        Ok(117440)
+    );
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0, solution.1.to_string())
+    let mut out = vec![];
+
+    find_a(
+        Some(Cursor::new(TEST1).lines()),
+        None,
+        Some("4,6,3,5,6,3,5,2,1,0"),
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(out, b"728\n");
+    assert!(find_a(Some(Cursor::new(TEST1).lines()), None, Some("8"), vec![]).is_err());
+
+    // Opcodes and operands are 3-bit numbers
+    assert_eq!(
+        part1(&parse(Cursor::new("Program: 0,1,8,4,3,0").lines())),
+        Err(String::from("bad program value 8, they go from 0 to 7"))
+    );
+    assert!(debug(
+        Some(Cursor::new("Program: 9,0").lines()),
+        None,
+        Cursor::new("list"),
+        vec![]
+    )
+    .is_err());
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
+advent_2024::day!("brute_force", parse, part1, part2_brute_force);
+advent_2024::day!("symbolic", parse, part1, part2_symbolic);
@@ -2,8 +2,8 @@ use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::io::{BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 7 } else { 71 };
-const FALLEN: usize = if cfg!(test) { 12 } else { 1024 };
+// Large enough for the input, the examples only use a corner of the grid
+const SIZE: usize = 71;
 const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
 
 struct UnionFind {
@@ -50,10 +50,10 @@ fn idx(x: usize, y: usize) -> usize {
     y * SIZE + x
 }
 
-fn neighbors(x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+fn neighbors(x: usize, y: usize, size: usize) -> impl Iterator<Item = (usize, usize)> {
     DIRS.iter().filter_map(move |&(dx, dy)| {
         let (nx, ny) = (x as i32 + dx, y as i32 + dy);
-        if nx >= 0 && ny >= 0 && nx < SIZE as i32 && ny < SIZE as i32 {
+        if nx >= 0 && ny >= 0 && nx < size as i32 && ny < size as i32 {
             Some((nx as usize, ny as usize))
         } else {
             None
@@ -61,7 +61,7 @@ fn neighbors(x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
     })
 }
 
-fn bfs(blocked: &[[bool; SIZE]; SIZE]) -> usize {
+fn bfs(blocked: &[[bool; SIZE]; SIZE], size: usize) -> usize {
     let mut visited = [[false; SIZE]; SIZE];
     let mut queue = VecDeque::new();
 
@@ -69,10 +69,10 @@ fn bfs(blocked: &[[bool; SIZE]; SIZE]) -> usize {
     queue.push_back((0usize, 0usize, 0usize));
 
     while let Some((x, y, dist)) = queue.pop_front() {
-        if x == SIZE - 1 && y == SIZE - 1 {
+        if x == size - 1 && y == size - 1 {
             return dist;
         }
-        for (nx, ny) in neighbors(x, y) {
+        for (nx, ny) in neighbors(x, y, size) {
             if !blocked[ny][nx] && !visited[ny][nx] {
                 visited[ny][nx] = true;
                 queue.push_back((nx, ny, dist + 1));
@@ -83,11 +83,17 @@ fn bfs(blocked: &[[bool; SIZE]; SIZE]) -> usize {
     0
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, String)
+struct Memory {
+    bytes: Vec<(usize, usize)>,
+    size: usize,
+    fallen: usize,
+}
+
+fn parse<T>(lines: Lines<T>) -> Memory
 where
     T: BufRead,
 {
-    let bytes: Vec<(usize, usize)> = lines
+    let bytes = lines
         .map(|line| {
             let line = line.unwrap();
             let mut parts = line.split(',');
@@ -96,26 +102,40 @@ where
             (x, y)
         })
         .collect();
+    let (size, fallen) = advent_2024::parameter!((7, 12), (SIZE, 1024));
+
+    Memory {
+        bytes,
+        size,
+        fallen,
+    }
+}
 
-    // p1: BFS after FALLEN bytes
+fn part1(memory: &Memory) -> usize {
+    // BFS after the first fallen bytes
     let mut blocked = [[false; SIZE]; SIZE];
-    for &(x, y) in &bytes[..FALLEN] {
+    for &(x, y) in &memory.bytes[..memory.fallen] {
         blocked[y][x] = true;
     }
-    let p1 = bfs(&blocked);
+    bfs(&blocked, memory.size)
+}
 
-    // p2: union-find in reverse — block everything, then restore bytes one by one
+fn part2(memory: &Memory) -> String {
+    let (bytes, size) = (&memory.bytes, memory.size);
+
+    // union-find in reverse — block everything, then restore bytes one by one
     // until start and end are connected
-    for &(x, y) in &bytes[FALLEN..] {
+    let mut blocked = [[false; SIZE]; SIZE];
+    for &(x, y) in bytes {
         blocked[y][x] = true;
     }
 
     let mut uf = UnionFind::new(SIZE * SIZE);
 
-    for y in 0..SIZE {
-        for x in 0..SIZE {
+    for y in 0..size {
+        for x in 0..size {
             if !blocked[y][x] {
-                for (nx, ny) in neighbors(x, y) {
+                for (nx, ny) in neighbors(x, y, size) {
                     if !blocked[ny][nx] {
                         uf.union(idx(x, y), idx(nx, ny));
                     }
@@ -125,13 +145,13 @@ where
     }
 
     let start = idx(0, 0);
-    let end = idx(SIZE - 1, SIZE - 1);
+    let end = idx(size - 1, size - 1);
     let mut p2 = String::new();
 
     for &(x, y) in bytes.iter().rev() {
         blocked[y][x] = false;
 
-        for (nx, ny) in neighbors(x, y) {
+        for (nx, ny) in neighbors(x, y, size) {
             if !blocked[ny][nx] {
                 uf.union(idx(x, y), idx(nx, ny));
             }
@@ -143,7 +163,7 @@ where
         }
     }
 
-    (p1, p2)
+    p2
 }
 
 #[test]
@@ -175,18 +195,9 @@ fn check() {
 2,0";
     use std::io::Cursor;
 
-    assert_eq!(
-        resolve(Cursor::new(TEST).lines()),
-        (22, String::from("6,1"))
-    );
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1)
+    assert_eq!((part1(&input), part2(&input)), (22, String::from("6,1")));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
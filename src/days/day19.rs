@@ -28,7 +28,15 @@ fn check_design_count<'a>(
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+type Onsen = (Vec<Vec<u8>>, Vec<Vec<u8>>);
+
+fn design_counts((patterns, designs): &Onsen) -> impl ParallelIterator<Item = usize> + use<'_> {
+    designs
+        .par_iter()
+        .map(|design| check_design_count(patterns, design, &mut HashMap::new()))
+}
+
+fn parse<T>(lines: Lines<T>) -> Onsen
 where
     T: BufRead,
 {
@@ -58,14 +66,15 @@ where
         }
     }
 
-    designs
-        .into_par_iter()
-        .map(|design| {
-            let n = check_design_count(&patterns, &design, &mut HashMap::new());
+    (patterns, designs)
+}
 
-            (if n > 0 { 1 } else { 0 }, n)
-        })
-        .reduce(|| (0, 0), |(p1, p2), (v1, v2)| (p1 + v1, p2 + v2))
+fn part1(onsen: &Onsen) -> usize {
+    design_counts(onsen).filter(|&n| n > 0).count()
+}
+
+fn part2(onsen: &Onsen) -> usize {
+    design_counts(onsen).sum()
 }
 
 #[test]
@@ -82,15 +91,9 @@ brgr
 bbrgwb";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (6, 16));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (6, 16));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
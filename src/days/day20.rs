@@ -1,8 +1,8 @@
 use std::io::{BufRead, Lines};
 
-const SIZE: usize = if cfg!(test) { 15 } else { 141 };
+// The examples are smaller, the rest of their grid is never raced
+const SIZE: usize = 141;
 const S: i32 = SIZE as i32;
-const SAVE: i32 = if cfg!(test) { 50 } else { 100 };
 
 type Point = (i32, i32);
 
@@ -73,7 +73,15 @@ impl Direction {
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+struct Racetrack {
+    racetrack: [[i32; SIZE]; SIZE],
+    start: Point,
+    end: Point,
+    // Picoseconds a cheat must save to count
+    save: i32,
+}
+
+fn parse<T>(lines: Lines<T>) -> Racetrack
 where
     T: BufRead,
 {
@@ -98,8 +106,20 @@ where
         }
     }
 
-    let mut p1 = 0;
-    let mut p2 = 0;
+    Racetrack {
+        racetrack,
+        start: pos,
+        end,
+        save: advent_2024::parameter!(50, 100),
+    }
+}
+
+// Counts the cheats of at most `cheat` picoseconds saving enough
+fn cheats(track: &Racetrack, cheat: u32) -> usize {
+    let mut racetrack = track.racetrack;
+    let mut pos = track.start;
+    let end = track.end;
+    let mut count = 0;
     let mut time = 0;
     let mut direction = Direction::new();
     let mut points = vec![];
@@ -146,14 +166,11 @@ where
                 if distance > 20 {
                     None
                 } else {
-                    if distance > 1 {
+                    if distance > 1 && distance <= cheat {
                         let saved = time - race - distance as i32;
 
-                        if saved >= SAVE {
-                            if distance == 2 {
-                                p1 += 1;
-                            }
-                            p2 += 1;
+                        if saved >= track.save {
+                            count += 1;
                         }
                     }
                     Some((p, race))
@@ -162,7 +179,15 @@ where
             .collect();
     }
 
-    (p1, p2)
+    count
+}
+
+fn part1(track: &Racetrack) -> usize {
+    cheats(track, 2)
+}
+
+fn part2(track: &Racetrack) -> usize {
+    cheats(track, 20)
 }
 
 #[test]
@@ -184,15 +209,9 @@ fn check() {
 ###############";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (1, 285));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (1, 285));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -1,99 +1,195 @@
-use std::collections::HashMap;
-use std::io::{BufRead, Lines};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, BufRead, Lines};
+use std::str::FromStr;
 
 type Point = (i32, i32);
 
+// Layouts of the puzzle, a space being the gap
+const NUMERICAL: &str = "789
+456
+123
+ 0A";
+const DIRECTIONAL: &str = " ^A
+<v>";
+
+// Longer sequences of presses are only counted
+const MAX_SHOWN: usize = 1 << 20;
+
+/// A keypad read from its layout, one row per line, spaces being gaps.
+#[derive(Debug, Clone)]
 struct Pad {
-    get_point: fn(u8) -> Point,
-    initial: Point,
-    dead: Point,
-}
-
-const DIRECTIONAL: Pad = Pad {
-    get_point: |key| match key {
-        b'^' => (1, 0),
-        b'A' => (2, 0),
-        b'<' => (0, 1),
-        b'v' => (1, 1),
-        b'>' => (2, 1),
-        _ => panic!(),
-    },
-    initial: (2, 0),
-    dead: (0, 0),
-};
-
-const NUMERICAL: Pad = Pad {
-    get_point: |key| match key {
-        b'7' => (0, 0),
-        b'8' => (1, 0),
-        b'9' => (2, 0),
-        b'4' => (0, 1),
-        b'5' => (1, 1),
-        b'6' => (2, 1),
-        b'1' => (0, 2),
-        b'2' => (1, 2),
-        b'3' => (2, 2),
-        b'0' => (1, 3),
-        b'A' => (2, 3),
-        _ => panic!(),
-    },
-    initial: (2, 3),
-    dead: (0, 3),
-};
-
-struct Keypad {
-    position: Point,
-    pad: &'static Pad,
+    keys: HashMap<u8, Point>,
+    cells: HashMap<Point, u8>,
 }
 
-impl Keypad {
-    fn new(pad: &'static Pad) -> Self {
-        let position = pad.initial;
+impl FromStr for Pad {
+    type Err = String;
 
-        Keypad { position, pad }
-    }
+    fn from_str(layout: &str) -> Result<Self, Self::Err> {
+        let mut keys = HashMap::new();
+
+        for (y, line) in layout.lines().enumerate() {
+            for (x, key) in line.bytes().enumerate() {
+                if key != b' ' && keys.insert(key, (x as i32, y as i32)).is_some() {
+                    return Err(format!("key {} is on the keypad twice", key as char));
+                }
+            }
+        }
 
-    fn numeric() -> Self {
-        Keypad::new(&NUMERICAL)
+        if !keys.contains_key(&b'A') {
+            return Err(String::from("the keypad has no A key"));
+        }
+
+        let pad = Pad {
+            cells: keys.iter().map(|(&key, &point)| (point, key)).collect(),
+            keys,
+        };
+
+        // A shortest path between two keys never goes back
+        for (&from, &a) in &pad.keys {
+            let distances = pad.distances(a);
+
+            for (&to, &b) in &pad.keys {
+                if distances.get(&b) != Some(&manhattan(a, b)) {
+                    return Err(format!(
+                        "key {} cannot be reached from {} without going back",
+                        to as char, from as char
+                    ));
+                }
+            }
+        }
+
+        Ok(pad)
     }
+}
+
+fn manhattan(a: Point, b: Point) -> i32 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
 
-    fn direction() -> Self {
-        Keypad::new(&DIRECTIONAL)
+impl Pad {
+    fn point(&self, key: u8) -> Point {
+        self.keys[&key]
     }
 
-    fn click(&mut self, key: u8) -> Vec<Vec<u8>> {
-        let mut ret = vec![];
-        let next = (self.pad.get_point)(key);
+    // The number of moves from a cell to each of the others
+    fn distances(&self, from: Point) -> HashMap<Point, i32> {
+        let mut distances = HashMap::from([(from, 0)]);
+        let mut queue = VecDeque::from([from]);
 
-        let dy = next.1 - self.position.1;
-        let dx = next.0 - self.position.0;
-        let dxdirection = if dx.is_positive() { b'>' } else { b'<' };
-        let dxabs = dx.unsigned_abs() as usize;
-        let dydirection = if dy.is_positive() { b'v' } else { b'^' };
-        let dyabs = dy.unsigned_abs() as usize;
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
 
-        // horizontal first
-        if dxabs != 0 && (self.position.0 + dx, self.position.1) != self.pad.dead {
-            let mut horizontal = vec![dxdirection; dxabs];
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let next = (point.0 + dx, point.1 + dy);
 
-            horizontal.resize(dxabs + dyabs, dydirection);
-            horizontal.push(b'A');
+                if self.cells.contains_key(&next) && !distances.contains_key(&next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
 
-            ret.push(horizontal);
+        distances
+    }
+
+    // The shortest moves from a key to another, avoiding the gaps, horizontal
+    // moves first. They are found one at a time as there may be many, only
+    // going through the cells from which the key is still as close
+    fn paths(&self, from: Point, to: Point) -> impl Iterator<Item = Vec<u8>> + '_ {
+        let distances = self.distances(to);
+        let dx = (to.0 - from.0).signum();
+        let dy = (to.1 - from.1).signum();
+        let mut stack = vec![(from, vec![])];
+
+        std::iter::from_fn(move || {
+            while let Some((point, path)) = stack.pop() {
+                if point == to {
+                    return Some(path);
+                }
+
+                // Pushed last to be explored first
+                for (step, direction) in [((0, dy), [b'^', b'v']), ((dx, 0), [b'<', b'>'])] {
+                    let next = (point.0 + step.0, point.1 + step.1);
+
+                    if next != point && distances.get(&next) == Some(&manhattan(next, to)) {
+                        let mut path = path.clone();
+
+                        path.push(direction[(step.0 + step.1 > 0) as usize]);
+                        stack.push((next, path));
+                    }
+                }
+            }
+
+            None
+        })
+    }
+
+    // The moves going all the way along one axis then along the other, if
+    // they avoid the gaps
+    fn straight(&self, from: Point, to: Point, horizontal_first: bool) -> Option<Vec<u8>> {
+        let horizontal = [b'<', b'>'][(to.0 > from.0) as usize];
+        let vertical = [b'^', b'v'][(to.1 > from.1) as usize];
+        let mut moves = [
+            vec![horizontal; (to.0 - from.0).unsigned_abs() as usize],
+            vec![vertical; (to.1 - from.1).unsigned_abs() as usize],
+        ];
+
+        if !horizontal_first {
+            moves.reverse();
         }
 
-        // vertical second
-        if dyabs != 0 && (self.position.0, self.position.1 + dy) != self.pad.dead {
-            let mut vertical = vec![dydirection; dyabs];
+        let path = moves.concat();
+        let mut point = from;
 
-            vertical.resize(dyabs + dxabs, dxdirection);
-            vertical.push(b'A');
+        for &direction in &path {
+            match direction {
+                b'<' => point.0 -= 1,
+                b'>' => point.0 += 1,
+                b'^' => point.1 -= 1,
+                _ => point.1 += 1,
+            }
 
-            ret.push(vertical);
+            if !self.cells.contains_key(&point) {
+                return None;
+            }
         }
 
+        Some(path)
+    }
+}
+
+struct Keypad<'a> {
+    position: Point,
+    pad: &'a Pad,
+}
+
+impl<'a> Keypad<'a> {
+    fn new(pad: &'a Pad) -> Self {
+        let position = pad.point(b'A');
+
+        Keypad { position, pad }
+    }
+
+    fn click(&mut self, key: u8) -> Vec<Vec<u8>> {
+        let next = self.pad.point(key);
+
+        // Changing direction once at most is never worse, zigzags are only
+        // there to go around gaps
+        let mut ret: Vec<Vec<u8>> = [true, false]
+            .into_iter()
+            .filter_map(|horizontal_first| self.pad.straight(self.position, next, horizontal_first))
+            .collect();
+
+        ret.dedup();
+
         if ret.is_empty() {
-            ret.push(vec![b'A']);
+            ret = self.pad.paths(self.position, next).collect();
+        }
+
+        for path in &mut ret {
+            path.push(b'A');
         }
 
         self.position = next;
@@ -104,18 +200,60 @@ impl Keypad {
 
 #[test]
 fn check_keypad() {
-    assert_eq!(Keypad::direction().click(b'<'), [b"v<<A"]);
-    assert_eq!(Keypad::direction().click(b'v'), [b"<vA", b"v<A"]);
+    let numerical: Pad = NUMERICAL.parse().unwrap();
+    let directional: Pad = DIRECTIONAL.parse().unwrap();
+
+    assert_eq!(Keypad::new(&directional).click(b'<'), [b"v<<A"]);
+    assert_eq!(Keypad::new(&directional).click(b'v'), [b"<vA", b"v<A"]);
 
-    let mut n = Keypad::numeric();
+    let mut n = Keypad::new(&numerical);
 
     assert_eq!(n.click(b'0'), [b"<A"]);
     assert_eq!(n.click(b'2'), [b"^A"]);
     assert_eq!(n.click(b'9'), [b">^^A", b"^^>A"]);
     assert_eq!(n.click(b'A'), [b"vvvA"]);
+
+    // Both corners are gaps
+    let zigzag: Pad = "A1 \n234\n 56".parse().unwrap();
+
+    assert_eq!(
+        Keypad::new(&zigzag).click(b'6'),
+        [b">v>vA", b">vv>A", b"v>>vA", b"v>v>A"]
+    );
+
+    assert_eq!(
+        "1A\n1".parse::<Pad>().err().unwrap(),
+        "key 1 is on the keypad twice"
+    );
+    assert_eq!(
+        "123".parse::<Pad>().err().unwrap(),
+        "the keypad has no A key"
+    );
+    assert!("A \n 1".parse::<Pad>().is_err());
+
+    // 90 keys, with 24310 shortest paths between the corners
+    let keys: Vec<u8> = (b'!'..=b'~').filter(|&key| key != b'A').collect();
+    let mut layout: Vec<String> = keys[..89]
+        .chunks(9)
+        .map(|row| String::from_utf8(row.to_vec()).unwrap())
+        .collect();
+
+    layout[9].push('A');
+
+    let large: Pad = layout.join("\n").parse().unwrap();
+
+    assert_eq!(
+        Keypad::new(&large).click(b'!'),
+        [b"<<<<<<<<^^^^^^^^^A", b"^^^^^^^^^<<<<<<<<A"]
+    );
 }
 
-fn get_complexity(caches: &mut [HashMap<Vec<u8>, usize>], level: usize, keys: &[u8]) -> usize {
+// The presses of the human to type the keys on the first keypad, each
+// keypad being pressed by a robot operated from the next one, the last
+// one from the directional keypad of the human
+fn get_complexity(caches: &mut [HashMap<Vec<u8>, usize>], pads: &[Pad], keys: &[u8]) -> usize {
+    let level = pads.len();
+
     if level == 0 {
         return keys.len();
     }
@@ -124,12 +262,12 @@ fn get_complexity(caches: &mut [HashMap<Vec<u8>, usize>], level: usize, keys: &[
         return complexity;
     }
 
-    let mut d = Keypad::direction();
+    let mut d = Keypad::new(&pads[0]);
     let complexity = keys.iter().fold(0, |c, &key| {
         let mut res = d
             .click(key)
             .into_iter()
-            .map(|seq| get_complexity(caches, level - 1, &seq))
+            .map(|seq| get_complexity(caches, &pads[1..], &seq))
             .collect::<Vec<_>>();
 
         res.sort_unstable();
@@ -144,51 +282,289 @@ fn get_complexity(caches: &mut [HashMap<Vec<u8>, usize>], level: usize, keys: &[
 
 #[test]
 fn check_complexity() {
+    let pads = vec![DIRECTIONAL.parse().unwrap(); 3];
     let mut caches = vec![HashMap::new(); 3];
 
-    assert_eq!(get_complexity(&mut caches, 0, b"v<<A"), 4);
-    assert_eq!(get_complexity(&mut caches, 1, b"v<<A"), 10);
+    assert_eq!(get_complexity(&mut caches, &pads[..0], b"v<<A"), 4);
+    assert_eq!(get_complexity(&mut caches, &pads[..1], b"v<<A"), 10);
+
+    assert_eq!(get_complexity(&mut caches, &pads[..2], b"<<^^A"), 23);
+    assert_eq!(get_complexity(&mut caches, &pads[..2], b"^^<<A"), 27);
+
+    assert_eq!(get_complexity(&mut caches, &pads, b"^^<<A"), 65);
+}
+
+// The presses counted by get_complexity, choosing the cheapest of the
+// candidates of each click
+fn get_sequence(caches: &mut [HashMap<Vec<u8>, usize>], pads: &[Pad], keys: &[u8]) -> Vec<u8> {
+    let Some((pad, next)) = pads.split_first() else {
+        return keys.to_vec();
+    };
+    let mut d = Keypad::new(pad);
+
+    keys.iter()
+        .flat_map(|&key| {
+            let seq = d
+                .click(key)
+                .into_iter()
+                .min_by_key(|seq| get_complexity(caches, next, seq))
+                .unwrap();
+
+            get_sequence(caches, next, &seq)
+        })
+        .collect()
+}
+
+// What the presses of the human on a directional keypad type on the first
+// keypad of the chain, each keypad of which is pressed by a robot. Each
+// robot starts on A and fails when its arm leaves its keypad
+fn replay(chain: &[Pad], presses: &[u8]) -> Result<Vec<u8>, String> {
+    let mut arms: Vec<Point> = chain.iter().map(|pad| pad.point(b'A')).collect();
+    let mut typed = vec![];
+
+    for (i, &press) in presses.iter().enumerate() {
+        let mut key = press;
+        let mut level = chain.len();
+
+        if !b"^v<>A".contains(&key) {
+            return Err(format!("press {} is {}, not a key", i + 1, key as char));
+        }
+
+        // Pressing A makes the robot press the key under its arm, down to the
+        // door
+        while level > 0 {
+            let arm = &mut arms[level - 1];
+
+            match key {
+                b'^' => arm.1 -= 1,
+                b'v' => arm.1 += 1,
+                b'<' => arm.0 -= 1,
+                b'>' => arm.0 += 1,
+                _ => {}
+            }
+
+            let Some(&under) = chain[level - 1].cells.get(arm) else {
+                return Err(format!(
+                    "press {} moves robot {level} off its keypad",
+                    i + 1
+                ));
+            };
+
+            if key != b'A' {
+                break;
+            }
+
+            key = under;
+            level -= 1;
+
+            if level > 0 && !b"^v<>A".contains(&key) {
+                return Err(format!(
+                    "press {} makes robot {} press {}, which moves no robot",
+                    i + 1,
+                    level + 1,
+                    key as char
+                ));
+            }
+        }
+
+        if level == 0 {
+            typed.push(key);
+        }
+    }
+
+    Ok(typed)
+}
+
+#[test]
+fn check_replay() {
+    let chain = puzzle_chain(2);
+    let mut caches = vec![HashMap::new(); chain.len()];
+    let presses = get_sequence(&mut caches, &chain, b"029A");
+
+    assert_eq!(presses.len(), 68);
+    assert_eq!(replay(&chain, &presses).unwrap(), b"029A");
+    assert_eq!(
+        replay(
+            &chain,
+            b"<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A"
+        )
+        .unwrap(),
+        b"029A"
+    );
+    assert_eq!(replay(&chain[..1], b"<A^A").unwrap(), b"02");
+    assert_eq!(
+        replay(&chain[..1], b"<A<A").err().unwrap(),
+        "press 3 moves robot 1 off its keypad"
+    );
+    assert_eq!(
+        replay(&chain, b"A1").err().unwrap(),
+        "press 2 is 1, not a key"
+    );
+
+    // A directional keypad with a key that moves no robot
+    let chain: Vec<Pad> = [NUMERICAL, " ^A\n<v>\nx  ", DIRECTIONAL]
+        .map(|layout| layout.parse().unwrap())
+        .into();
+
+    assert_eq!(
+        replay(&chain, b"v<<A>A<A>A>^A").err().unwrap(),
+        "press 13 makes robot 2 press x, which moves no robot"
+    );
+}
+
+// The digits of the code, ignoring the other keys
+fn numeric_part(code: &str) -> usize {
+    code.bytes()
+        .filter(u8::is_ascii_digit)
+        .fold(0, |n, digit| n * 10 + (digit - b'0') as usize)
+}
+
+// The fewest presses of the human to type each code on the first keypad of
+// the chain, the robot of which starts on A for each code
+fn presses(codes: &[String], chain: &[Pad]) -> Vec<usize> {
+    let mut caches = vec![HashMap::new(); chain.len() - 1];
+
+    codes
+        .iter()
+        .map(|code| {
+            let mut door = Keypad::new(&chain[0]);
+
+            code.as_bytes().iter().fold(0, |c, &key| {
+                let seqs = door.click(key);
+
+                let mut complexities = seqs
+                    .iter()
+                    .map(|seq| get_complexity(&mut caches, &chain[1..], seq))
+                    .collect::<Vec<_>>();
+
+                complexities.sort_unstable();
+
+                c + complexities[0]
+            })
+        })
+        .collect()
+}
+
+fn complexity(codes: &[String], chain: &[Pad]) -> usize {
+    codes
+        .iter()
+        .zip(presses(codes, chain))
+        .map(|(code, presses)| numeric_part(code) * presses)
+        .sum()
+}
+
+// The numerical keypad of the door, then as many directional ones
+fn puzzle_chain(robots: usize) -> Vec<Pad> {
+    let mut chain = vec![NUMERICAL.parse().unwrap()];
+
+    chain.resize(robots + 1, DIRECTIONAL.parse().unwrap());
+    chain
+}
+
+fn parse<T>(lines: Lines<T>) -> Vec<String>
+where
+    T: BufRead,
+{
+    lines.map(|line| line.unwrap()).collect()
+}
+
+fn part1(codes: &[String]) -> usize {
+    complexity(codes, &puzzle_chain(2))
+}
+
+fn part2(codes: &[String]) -> usize {
+    complexity(codes, &puzzle_chain(25))
+}
+
+// Reads `numerical`, `directional` or a file with a layout, followed by
+// `*n` to repeat it
+fn read_chain(layouts: &[String]) -> Result<Vec<Pad>, String> {
+    let mut chain: Vec<Pad> = vec![];
+
+    for layout in layouts {
+        let (name, count) = match layout.rsplit_once('*') {
+            Some((name, count)) => (
+                name,
+                count.parse().map_err(|_| format!("bad count {count}"))?,
+            ),
+            None => (layout.as_str(), 1),
+        };
+        let pad: Pad = match name {
+            "numerical" => NUMERICAL.parse(),
+            "directional" => DIRECTIONAL.parse(),
+            path => fs::read_to_string(path)
+                .map_err(|error| format!("{path}: {error}"))?
+                .parse(),
+        }
+        .map_err(|error| format!("{name}: {error}"))?;
+
+        chain.extend(std::iter::repeat_n(pad, count));
+    }
+
+    if chain.is_empty() {
+        return Err(String::from("no keypad"));
+    }
 
-    assert_eq!(get_complexity(&mut caches, 2, b"<<^^A"), 23);
-    assert_eq!(get_complexity(&mut caches, 2, b"^^<<A"), 27);
+    // The robots press the keys sent by the next keypad
+    for (i, pad) in chain.iter().enumerate().skip(1) {
+        if let Some(&key) = b"^<v>".iter().find(|key| !pad.keys.contains_key(key)) {
+            return Err(format!(
+                "keypad {} has no {} key to move a robot",
+                i + 1,
+                key as char
+            ));
+        }
+    }
 
-    assert_eq!(get_complexity(&mut caches, 3, b"^^<<A"), 65);
+    Ok(chain)
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, usize)
+/// Prints the fewest presses of the human to type each code of the input on
+/// the first keypad of the chain, then the total complexity. With `show`,
+/// prints the presses themselves once replayed through the chain.
+pub fn chain<T, W>(lines: Lines<T>, layouts: &[String], show: bool, mut out: W) -> io::Result<()>
 where
     T: BufRead,
+    W: io::Write,
 {
-    let mut numeric = Keypad::numeric();
-    let mut caches = vec![HashMap::new(); 25];
+    let invalid = |error| io::Error::new(io::ErrorKind::InvalidInput, error);
+    let chain = read_chain(layouts).map_err(invalid)?;
+    let codes = parse(lines);
 
-    lines.fold((0, 0), |(p1, p2), line| {
-        let line = line.unwrap();
-        let numeric_part = line[0..(line.len() - 1)].parse::<usize>().unwrap();
+    for code in &codes {
+        if let Some(key) = code.bytes().find(|key| !chain[0].keys.contains_key(key)) {
+            return Err(invalid(format!("no key {} for code {code}", key as char)));
+        }
+    }
 
-        line.as_bytes().iter().fold((p1, p2), |(c1, c2), &c| {
-            let seqs = numeric.click(c);
+    let mut complexity = 0;
 
-            let mut complexities_2 = seqs
-                .iter()
-                .map(|seq| get_complexity(&mut caches, 2, seq))
-                .collect::<Vec<_>>();
+    let mut caches = vec![HashMap::new(); chain.len()];
 
-            complexities_2.sort_unstable();
+    for (code, presses) in codes.iter().zip(presses(&codes, &chain)) {
+        write!(out, "{code}: {presses} presses")?;
+        complexity += numeric_part(code) * presses;
 
-            let mut complexities_25 = seqs
-                .iter()
-                .map(|seq| get_complexity(&mut caches, 25, seq))
-                .collect::<Vec<_>>();
+        if !show {
+            writeln!(out)?;
+        } else if presses > MAX_SHOWN {
+            writeln!(out, ", too many to show")?;
+        } else {
+            let sequence = get_sequence(&mut caches, &chain, code.as_bytes());
+            let typed = replay(&chain, &sequence).map_err(io::Error::other)?;
 
-            complexities_25.sort_unstable();
+            if typed != code.as_bytes() {
+                return Err(io::Error::other(format!(
+                    "the presses type {} instead of {code}",
+                    String::from_utf8_lossy(&typed)
+                )));
+            }
 
-            (
-                c1 + complexities_2[0] * numeric_part,
-                c2 + complexities_25[0] * numeric_part,
-            )
-        })
-    })
+            writeln!(out, ": {}", String::from_utf8_lossy(&sequence))?;
+        }
+    }
+
+    writeln!(out, "complexity: {complexity}")
 }
 
 #[test]
@@ -200,15 +576,37 @@ fn check() {
 379A";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()).0, 126384);
-}
-
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!(part1(&parse(Cursor::new(TEST).lines())), 126384);
+
+    let mut out = vec![];
+    let layouts = ["numerical", "directional*2"].map(String::from);
+
+    chain(Cursor::new(TEST).lines(), &layouts, false, &mut out).unwrap();
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .starts_with("029A: 68 presses\n980A: 60 presses\n"));
+
+    let mut out = vec![];
+
+    chain(Cursor::new(TEST).lines(), &layouts[..1], true, &mut out).unwrap();
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .starts_with("029A: 12 presses: <A^A>^^AvvvA\n"));
+
+    // Codes that do not end on A are typed from A as well
+    let mut out = vec![];
+
+    chain(Cursor::new("02\nA").lines(), &layouts[..1], true, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "02: 4 presses: <A^A\nA: 1 presses: A\ncomplexity: 8\n"
+    );
+    assert_eq!(
+        read_chain(&["numerical", "numerical"].map(String::from))
+            .err()
+            .unwrap(),
+        "keypad 2 has no ^ key to move a robot"
+    );
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -74,12 +74,9 @@ fn check_array() {
     assert_eq!(sequence, 4 * BASE * BASE * BASE + 17 * BASE + 8);
 }
 
-type Prices = (u64, Vec<i32>);
-
-fn compute_buyers(secrets: &[Secret]) -> Prices {
+fn compute_buyers(secrets: &[Secret]) -> Vec<i32> {
     let mut prices = vec![0; PRICES_SIZE];
     let mut it_was_buyer = vec![u32::MAX; PRICES_SIZE];
-    let mut p1 = 0u64;
 
     for (index, &(mut secret)) in secrets.iter().enumerate() {
         let mut seq: usize = 0;
@@ -100,40 +97,42 @@ fn compute_buyers(secrets: &[Secret]) -> Prices {
 
             prev = price;
         }
-
-        p1 += secret as u64;
     }
 
-    (p1, prices)
+    prices
 }
 
-fn resolve<T>(lines: Lines<T>) -> (u64, i32)
+fn parse<T>(lines: Lines<T>) -> Vec<Secret>
 where
     T: BufRead,
 {
-    let chunk_size = BUYERS_COUNT.div_ceil(rayon::current_num_threads());
-
-    let (p1, prices) = lines
+    lines
         .map(|line| line.unwrap().parse::<u32>().unwrap())
-        .collect::<Vec<_>>()
-        .par_chunks(chunk_size)
-        .map(compute_buyers)
-        .reduce(
-            || (0, vec![0; PRICES_SIZE]),
-            |mut a: Prices, b: Prices| {
-                a.0 += b.0;
+        .collect()
+}
 
-                for (va, vb) in a.1.iter_mut().zip(b.1) {
-                    *va += vb;
-                }
+fn part1(secrets: &[Secret]) -> u64 {
+    secrets
+        .par_iter()
+        .map(|&secret| (0..2000).fold(secret, |s, _| next_secret(s)) as u64)
+        .sum()
+}
 
-                a
-            },
-        );
+fn part2(secrets: &[Secret]) -> i32 {
+    let chunk_size = BUYERS_COUNT.div_ceil(rayon::current_num_threads());
 
-    let p2 = prices.into_iter().max().unwrap();
+    let prices = secrets.par_chunks(chunk_size).map(compute_buyers).reduce(
+        || vec![0; PRICES_SIZE],
+        |mut a, b| {
+            for (va, vb) in a.iter_mut().zip(b) {
+                *va += vb;
+            }
 
-    (p1, p2)
+            a
+        },
+    );
+
+    prices.into_iter().max().unwrap()
 }
 
 #[test]
@@ -148,16 +147,8 @@ fn check() {
 2024";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST1).lines()).0, 37327623);
-    assert_eq!(resolve(Cursor::new(TEST2).lines()).1, 23);
-}
-
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!(part1(&parse(Cursor::new(TEST1).lines())), 37327623);
+    assert_eq!(part2(&parse(Cursor::new(TEST2).lines())), 23);
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
@@ -46,7 +46,60 @@ fn best_clique(
     }
 }
 
-fn resolve<T>(lines: Lines<T>) -> (usize, String)
+// Bron-Kerbosch with pivoting
+fn bron_kerbosch(
+    connections: &HashMap<u16, HashSet<u16>>,
+    clique: &mut Vec<u16>,
+    mut p: HashSet<u16>,
+    mut x: HashSet<u16>,
+    best: &mut Vec<u16>,
+) {
+    if p.is_empty() && x.is_empty() {
+        if clique.len() > best.len() {
+            *best = clique.clone();
+        }
+        return;
+    }
+
+    let pivot = *p
+        .union(&x)
+        .max_by_key(|v| connections[v].intersection(&p).count())
+        .unwrap();
+    let candidates: Vec<u16> = p.difference(&connections[&pivot]).copied().collect();
+
+    for v in candidates {
+        let neighbors = &connections[&v];
+
+        clique.push(v);
+        bron_kerbosch(
+            connections,
+            clique,
+            p.intersection(neighbors).copied().collect(),
+            x.intersection(neighbors).copied().collect(),
+            best,
+        );
+        clique.pop();
+
+        p.remove(&v);
+        x.insert(v);
+    }
+}
+
+fn password(clique: impl IntoIterator<Item = u16>) -> String {
+    let mut points: Vec<u16> = Vec::from_iter(clique);
+
+    points.sort_unstable();
+
+    points
+        .iter()
+        .map(|&p| format!("{}{}", (p >> 8) as u8 as char, (p & 0xff) as u8 as char))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+type Connections = HashMap<u16, HashSet<u16>>;
+
+fn parse<T>(lines: Lines<T>) -> Connections
 where
     T: BufRead,
 {
@@ -63,11 +116,15 @@ where
         connections.entry(b).or_default().insert(a);
     }
 
+    connections
+}
+
+fn part1(connections: &Connections) -> usize {
     const T: u16 = (b't' as u16) << 8;
 
     let mut p1 = 0;
 
-    for (k, v) in &connections {
+    for (k, v) in connections {
         for i in v {
             if i < k {
                 continue;
@@ -87,22 +144,25 @@ where
         }
     }
 
+    p1
+}
+
+fn part2(connections: &Connections) -> String {
     let p: HashSet<u16> = connections.keys().copied().collect();
     let mut max_clique = HashSet::new();
 
-    best_clique(&connections, HashSet::new(), p, &mut max_clique);
+    best_clique(connections, HashSet::new(), p, &mut max_clique);
 
-    let mut points: Vec<u16> = Vec::from_iter(max_clique);
+    password(max_clique)
+}
 
-    points.sort_unstable();
+fn part2_bron_kerbosch(connections: &Connections) -> String {
+    let p: HashSet<u16> = connections.keys().copied().collect();
+    let mut max_clique = vec![];
 
-    let p2 = points
-        .iter()
-        .map(|&p| format!("{}{}", (p >> 8) as u8 as char, (p & 0xff) as u8 as char))
-        .collect::<Vec<_>>()
-        .join(",");
+    bron_kerbosch(connections, &mut vec![], p, HashSet::new(), &mut max_clique);
 
-    (p1, p2)
+    password(max_clique)
 }
 
 #[test]
@@ -141,18 +201,14 @@ tb-vc
 td-yn";
     use std::io::Cursor;
 
+    let input = parse(Cursor::new(TEST).lines());
+
     assert_eq!(
-        resolve(Cursor::new(TEST).lines()),
+        (part1(&input), part2(&input)),
         (7, String::from("co,de,ka,ta"))
     );
+    assert_eq!(part2_bron_kerbosch(&input), "co,de,ka,ta");
 }
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1)
-}
-
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
+advent_2024::day!("bron_kerbosch", parse, part1, part2_bron_kerbosch);
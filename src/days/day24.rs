@@ -1,39 +1,30 @@
 use regex::Regex;
-use std::collections::HashMap;
-use std::io::{BufRead, Lines};
-
-#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
-enum Operation {
-    Value(bool),
-    Xor(u32, u32),
-    Or(u32, u32),
-    And(u32, u32),
-}
+use std::io::{self, BufRead, Lines};
 
-fn convert(name: &[u8]) -> u32 {
-    name[2] as u32 | ((name[1] as u32) << 8) | ((name[0] as u32) << 16)
-}
+mod export;
+mod netlist;
+mod repair;
 
-fn compute(tree: &HashMap<u32, Operation>, name: u32) -> Option<bool> {
-    let operation = tree.get(&name)?;
+use netlist::{Kind, Netlist};
+use repair::Swap;
 
-    Some(match *operation {
-        Operation::Value(x) => x,
-        Operation::Xor(a, b) => compute(tree, a)? != compute(tree, b)?,
-        Operation::Or(a, b) => compute(tree, a)? || compute(tree, b)?,
-        Operation::And(a, b) => compute(tree, a)? && compute(tree, b)?,
-    })
+struct Circuit {
+    netlist: Netlist,
+    // The initial values of the wires, the x and y buses
+    inputs: Vec<(String, bool)>,
 }
 
-fn resolve<T>(lines: Lines<T>) -> (u64, String)
+// Fails on lines that are neither an initial value nor a gate, and on
+// netlists that cannot be evaluated
+fn parse<T>(lines: Lines<T>) -> Result<Circuit, String>
 where
     T: BufRead,
 {
-    let re_init = Regex::new(r"(\S+): (\d)").unwrap();
-    let re_rule = Regex::new(r"(\S+) (\w+) (\S+) -> (\S+)").unwrap();
+    let re_init = Regex::new(r"^(\S+): ([01])$").unwrap();
+    let re_rule = Regex::new(r"^(\S+) (AND|OR|XOR) (\S+) -> (\S+)$").unwrap();
     let mut is_rule = false;
-    let mut tree: HashMap<u32, Operation> = HashMap::new();
-    let mut inverted_tree: HashMap<Operation, u32> = HashMap::new();
+    let mut gates = vec![];
+    let mut inputs = vec![];
 
     for line in lines {
         let line = line.unwrap();
@@ -44,142 +35,127 @@ where
         }
 
         if is_rule {
-            let caps = re_rule.captures(&line).unwrap();
-
-            let a = convert(caps.get(1).unwrap().as_str().as_bytes());
-            let b = convert(caps.get(3).unwrap().as_str().as_bytes());
-            let c = convert(caps.get(4).unwrap().as_str().as_bytes());
-            let ope = caps.get(2).unwrap().as_str();
-
-            let (a, b) = if a > b { (b, a) } else { (a, b) };
-
-            let operation = match ope {
-                "XOR" => Operation::Xor(a, b),
-                "OR" => Operation::Or(a, b),
-                "AND" => Operation::And(a, b),
-                _ => panic!(),
+            let caps = re_rule
+                .captures(&line)
+                .ok_or(format!("bad gate \"{line}\""))?;
+
+            let kind = match &caps[2] {
+                "XOR" => Kind::Xor,
+                "OR" => Kind::Or,
+                _ => Kind::And,
             };
 
-            tree.insert(c, operation);
-            inverted_tree.insert(operation, c);
+            gates.push((
+                caps[4].to_string(),
+                kind,
+                caps[1].to_string(),
+                caps[3].to_string(),
+            ));
         } else {
-            let caps = re_init.captures(&line).unwrap();
+            let caps = re_init
+                .captures(&line)
+                .ok_or(format!("bad initial value \"{line}\""))?;
 
-            let name = convert(caps.get(1).unwrap().as_str().as_bytes());
-            let value = caps.get(2).unwrap().as_str().parse::<u32>().unwrap();
-
-            tree.insert(name, Operation::Value(value == 1));
+            inputs.push((caps[1].to_string(), &caps[2] == "1"));
         }
     }
 
-    let mut p1: u64 = 0;
-    let mut count = 0;
+    let netlist = Netlist::new(
+        gates
+            .iter()
+            .map(|(output, kind, a, b)| (output.as_str(), *kind, a.as_str(), b.as_str())),
+    )?;
 
-    while let Some(bit) = compute(
-        &tree,
-        convert(&[b'z', b'0' + count / 10, b'0' + count % 10]),
-    ) {
-        p1 |= if bit { 1 << count } else { 0 };
-        count += 1;
-    }
+    Ok(Circuit { netlist, inputs })
+}
+
+fn part1(circuit: &Result<Circuit, String>) -> Result<u64, String> {
+    let circuit = circuit.as_ref().map_err(Clone::clone)?;
+    let netlist = &circuit.netlist;
+    let mut values = vec![false; netlist.len()];
 
-    if cfg!(test) {
-        return (p1, String::new());
+    // Inputs no gate reads are not wires of the netlist
+    for (name, value) in &circuit.inputs {
+        if let Some(wire) = netlist.wire(name) {
+            values[wire] = *value;
+        }
     }
 
-    // https://www.101computing.net/binary-additions-using-logic-gates/
-    let mut inverted = vec![];
-    let mut x = convert(b"x00");
-    let mut y = convert(b"y00");
-    let mut z = convert(b"z00");
-    let s = *inverted_tree.get(&Operation::Xor(x, y)).unwrap();
-    let mut cin = *inverted_tree.get(&Operation::And(x, y)).unwrap();
+    netlist.evaluate(&mut values);
+    netlist::to_number(&netlist::get_bus(&values, &netlist.bus('z')))
+        .ok_or(String::from("the z bus holds more than 64 bits"))
+}
 
-    if s != z {
-        inverted.extend([s, z]);
-    }
+fn part2(circuit: &Result<Circuit, String>) -> Result<String, String> {
+    let circuit = circuit.as_ref().map_err(Clone::clone)?;
 
-    for i in 1..(count - 1) {
-        let digits = (b'0' + i / 10, b'0' + i % 10);
+    // The examples are not adders
+    if advent_2024::example!() {
+        return Ok(String::new());
+    }
 
-        x = convert(&[b'x', digits.0, digits.1]);
-        y = convert(&[b'y', digits.0, digits.1]);
-        z = convert(&[b'z', digits.0, digits.1]);
+    repair::repair(&circuit.netlist).map(|swaps| answer(&swaps))
+}
 
-        let mut s1 = *inverted_tree.get(&Operation::Xor(x, y)).unwrap();
+/// Returns the sorted names of the swapped wires, separated by commas.
+fn answer(swaps: &[Swap]) -> String {
+    let mut wires: Vec<&str> = swaps
+        .iter()
+        .flat_map(|swap| swap.wires.iter().map(String::as_str))
+        .collect();
 
-        let (mut a, mut b) = if cin < s1 { (cin, s1) } else { (s1, cin) };
-        let s2 = if let Some(v) = inverted_tree.get(&Operation::Xor(a, b)) {
-            *v
-        } else {
-            // Here we want to know if the bad wire is s1 or cin so
-            // let's look up the other way by checking z
-            //
-            // This code is ugly.
-            if let Some(Operation::Xor(left, right)) = tree.get(&z) {
-                let (i, j): (&mut u32, &u32);
-
-                if *left == s1 {
-                    i = &mut cin;
-                    j = right;
-                } else if *right == s1 {
-                    i = &mut cin;
-                    j = left;
-                } else if *left == cin {
-                    i = &mut s1;
-                    j = right;
-                } else if *right == cin {
-                    i = &mut s1;
-                    j = left;
-                } else {
-                    panic!();
-                }
-
-                inverted.extend([*i, *j]);
-
-                let ope = tree.get(j).unwrap();
-                inverted_tree.insert(*ope, *i);
-
-                *i = *j;
-            }
-
-            (a, b) = if cin < s1 { (cin, s1) } else { (s1, cin) };
-            *inverted_tree.get(&Operation::Xor(a, b)).unwrap()
-        };
-
-        if s2 != z {
-            inverted_tree.insert(*tree.get(&z).unwrap(), s2);
-
-            inverted.extend([s2, z]);
-        }
+    wires.sort_unstable();
+    wires.join(",")
+}
 
-        let cout1 = *inverted_tree.get(&Operation::And(x, y)).unwrap();
-        let cout2 = *inverted_tree.get(&Operation::And(a, b)).unwrap();
+/// Prints why each wire of the answer of part 2 is swapped.
+pub fn explain<T, W>(lines: Lines<T>, mut out: W) -> io::Result<()>
+where
+    T: BufRead,
+    W: io::Write,
+{
+    let invalid = |error| io::Error::new(io::ErrorKind::InvalidData, error);
+    let circuit = parse(lines).map_err(invalid)?;
+    let swaps = repair::repair(&circuit.netlist).map_err(invalid)?;
 
-        let (a, b) = if cout1 < cout2 {
-            (cout1, cout2)
-        } else {
-            (cout2, cout1)
-        };
-        cin = *inverted_tree.get(&Operation::Or(a, b)).unwrap();
+    for swap in &swaps {
+        writeln!(out, "{swap}")?;
     }
 
-    inverted.sort_unstable();
+    writeln!(out, "{}", answer(&swaps))
+}
 
-    let p2 = inverted
-        .iter()
-        .map(|v| {
-            format!(
-                "{}{}{}",
-                (v >> 16) as u8 as char,
-                ((v >> 8) & 0xff) as u8 as char,
-                (v & 0xff) as u8 as char
-            )
-        })
-        .collect::<Vec<_>>()
-        .join(",");
-
-    (p1, p2)
+/// The languages the circuit can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Language {
+    Dot,
+    Verilog,
+}
+
+/// Writes the circuit as a Graphviz graph, with the wires the repair would
+/// swap highlighted, or as a Verilog module.
+pub fn export<T, W>(lines: Lines<T>, language: Language, mut out: W) -> io::Result<()>
+where
+    T: BufRead,
+    W: io::Write,
+{
+    let circuit =
+        parse(lines).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    match language {
+        Language::Dot => {
+            // Nothing is highlighted in a circuit that is not an adder
+            let swapped: Vec<usize> = repair::repair(&circuit.netlist)
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|swap| swap.wires.iter())
+                .filter_map(|name| circuit.netlist.wire(name))
+                .collect();
+
+            write!(out, "{}", export::dot(&circuit.netlist, &swapped))
+        }
+        Language::Verilog => write!(out, "{}", export::verilog(&circuit.netlist, "circuit")),
+    }
 }
 
 #[test]
@@ -243,16 +219,34 @@ tgd XOR rvg -> z12
 tnw OR pbm -> gnj";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST1).lines()).0, 4);
-    assert_eq!(resolve(Cursor::new(TEST2).lines()).0, 2024);
-}
-
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1)
+    assert_eq!(part1(&parse(Cursor::new(TEST1).lines())), Ok(4));
+    assert_eq!(part1(&parse(Cursor::new(TEST2).lines())), Ok(2024));
+
+    // Buses of 64 bits and more, whose value may not fit in the answer
+    let circuit = |width: usize| {
+        let inputs: String = (0..width).map(|i| format!("x{i:0>2}: 1\n")).collect();
+        let gates: String = (0..width)
+            .map(|i| format!("x{i:0>2} AND x{i:0>2} -> z{i:0>2}\n"))
+            .collect();
+
+        parse(Cursor::new(format!("{inputs}\n{gates}")).lines())
+    };
+
+    assert_eq!(part1(&circuit(64)), Ok(u64::MAX));
+    assert_eq!(
+        part1(&parse(
+            Cursor::new("x00: 1\n\nx00 AND a -> b\nb OR x00 -> a").lines()
+        )),
+        Err(String::from("loop through b -> a"))
+    );
+    assert_eq!(
+        part2(&parse(Cursor::new("x00: 1\n\nx00 NAND x00 -> z00").lines())),
+        Err(String::from("bad gate \"x00 NAND x00 -> z00\""))
+    );
+    assert_eq!(
+        part1(&circuit(65)),
+        Err(String::from("the z bus holds more than 64 bits"))
+    );
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
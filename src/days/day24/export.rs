@@ -0,0 +1,209 @@
+use super::netlist::{Kind, Netlist};
+use std::fmt::Write;
+
+fn colour(kind: Kind) -> &'static str {
+    match kind {
+        Kind::And => "lightblue",
+        Kind::Or => "palegreen",
+        Kind::Xor => "gold",
+    }
+}
+
+fn inputs(netlist: &Netlist) -> Vec<usize> {
+    let mut inputs: Vec<usize> = (0..netlist.len())
+        .filter(|&wire| netlist.driver(wire).is_none())
+        .collect();
+
+    inputs.sort_unstable_by_key(|&wire| netlist.name(wire));
+    inputs
+}
+
+/// The netlist as a Graphviz graph, one node per wire with its gate, the
+/// `highlighted` wires circled in red.
+pub fn dot(netlist: &Netlist, highlighted: &[usize]) -> String {
+    let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n    node [style=filled];\n");
+
+    for wire in inputs(netlist) {
+        writeln!(
+            dot,
+            "    {} [shape=circle, fillcolor=white];",
+            netlist.name(wire)
+        )
+        .unwrap();
+    }
+
+    for &wire in netlist.driven() {
+        let kind = netlist.driver(wire).unwrap().kind;
+
+        write!(
+            dot,
+            "    {0} [shape=box, fillcolor={1}, label=\"{0}\\n{kind}\"",
+            netlist.name(wire),
+            colour(kind)
+        )
+        .unwrap();
+
+        if highlighted.contains(&wire) {
+            dot.push_str(", color=red, penwidth=3");
+        }
+
+        dot.push_str("];\n");
+    }
+
+    for &wire in netlist.driven() {
+        for input in netlist.driver(wire).unwrap().inputs {
+            writeln!(
+                dot,
+                "    {} -> {};",
+                netlist.name(input),
+                netlist.name(wire)
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+// The reserved words of Verilog (IEEE 1364-2005)
+const KEYWORDS: &str = "\
+    always and assign automatic begin buf bufif0 bufif1 case casex casez cell \
+    cmos config deassign default defparam design disable edge else end endcase \
+    endconfig endfunction endgenerate endmodule endprimitive endspecify \
+    endtable endtask event for force forever fork function generate genvar \
+    highz0 highz1 if ifnone incdir include initial inout input instance \
+    integer join large liblist library localparam macromodule medium module \
+    nand negedge nmos nor noshowcancelled not notif0 notif1 or output \
+    parameter pmos posedge primitive pull0 pull1 pulldown pullup \
+    pulsestyle_onevent pulsestyle_ondetect rcmos real realtime reg release \
+    repeat rnmos rpmos rtran rtranif0 rtranif1 scalared showcancelled signed \
+    small specify specparam strong0 strong1 supply0 supply1 table task time \
+    tran tranif0 tranif1 tri tri0 tri1 triand trior trireg unsigned use uwire \
+    vectored wait wand weak0 weak1 while wire wor xnor xor";
+
+// Names that are not plain identifiers, like keywords, are escaped: a
+// backslash before and a space after
+fn identifier(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if plain && !KEYWORDS.split_whitespace().any(|keyword| keyword == name) {
+        name.to_string()
+    } else {
+        format!("\\{name} ")
+    }
+}
+
+/// The netlist as a structural Verilog module, one gate primitive per
+/// driven wire. The z bus gives the outputs, even the z wires a miswired
+/// circuit reads.
+pub fn verilog(netlist: &Netlist, module: &str) -> String {
+    let outputs = netlist.bus('z');
+    let name = |wire| identifier(netlist.name(wire));
+    let mut ports: Vec<String> = inputs(netlist)
+        .into_iter()
+        .map(|wire| format!("input wire {}", name(wire)))
+        .collect();
+
+    ports.extend(
+        outputs
+            .iter()
+            .map(|&wire| format!("output wire {}", name(wire))),
+    );
+
+    let mut verilog = format!(
+        "module {} (\n    {}\n);\n",
+        identifier(module),
+        ports.join(",\n    ")
+    );
+    let internal: Vec<String> = netlist
+        .driven()
+        .iter()
+        .filter(|wire| !outputs.contains(wire))
+        .map(|&wire| name(wire))
+        .collect();
+
+    if !internal.is_empty() {
+        writeln!(verilog, "    wire {};", internal.join(", ")).unwrap();
+    }
+
+    for &wire in netlist.driven() {
+        let gate = netlist.driver(wire).unwrap();
+
+        writeln!(
+            verilog,
+            "    {} {} ({}, {}, {});",
+            gate.kind.to_string().to_lowercase(),
+            identifier(&format!("g_{}", netlist.name(wire))),
+            name(wire),
+            name(gate.inputs[0]),
+            name(gate.inputs[1])
+        )
+        .unwrap();
+    }
+
+    verilog.push_str("endmodule\n");
+    verilog
+}
+
+#[test]
+fn check_export() {
+    use super::netlist;
+
+    let netlist = netlist::connect(&netlist::adder(2)).unwrap();
+    let verilog = verilog(&netlist, "adder");
+
+    assert!(verilog.starts_with(
+        "module adder (
+    input wire x00,
+    input wire x01,
+    input wire y00,
+    input wire y01,
+    output wire z00,
+    output wire z01,
+    output wire z02
+);
+"
+    ));
+    assert!(verilog.contains("    xor g_z01 (z01, s01, g00);\n"));
+    assert!(verilog.contains("    or g_z02 (z02, p01, g01);\n"));
+    assert!(verilog.contains("    wire g00, s01, g01, p01;\n"));
+    assert!(verilog.ends_with("endmodule\n"));
+
+    // z00 feeds the carry once swapped, it is still an output
+    let mut swapped = netlist::connect(&netlist::adder(2)).unwrap();
+
+    swapped
+        .swap(swapped.wire("z00").unwrap(), swapped.wire("g00").unwrap())
+        .unwrap();
+    assert!(self::verilog(&swapped, "adder").contains("    output wire z00,\n"));
+    assert!(self::verilog(&swapped, "adder").contains("    wire g00, s01, g01, p01;\n"));
+
+    let mut gates = netlist::adder(2);
+
+    for gate in &mut gates {
+        for name in [&mut gate.0, &mut gate.2, &mut gate.3] {
+            if name == "p01" {
+                *name = String::from("and");
+            }
+        }
+    }
+
+    let keywords = self::verilog(&netlist::connect(&gates).unwrap(), "and");
+
+    assert!(keywords.starts_with("module \\and  (\n"));
+    assert!(keywords.contains("    and g_and (\\and , s01, g00);\n"));
+    assert!(keywords.contains("    or g_z02 (z02, \\and , g01);\n"));
+
+    let g00 = netlist.wire("g00").unwrap();
+    let dot = dot(&netlist, &[g00]);
+
+    assert!(dot.starts_with("digraph circuit {\n"));
+    assert!(dot.contains("    x00 [shape=circle, fillcolor=white];\n"));
+    assert!(dot.contains("    z00 [shape=box, fillcolor=gold, label=\"z00\\nXOR\"];\n"));
+    assert!(dot.contains("    p01 -> z02;\n"));
+    assert_eq!(dot.matches("color=red").count(), 1);
+}
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{BitAnd, BitOr, BitXor};
+
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum Kind {
+    And,
+    Or,
+    Xor,
+}
+
+impl Kind {
+    /// Applies the gate to single bits or, with integers, to as many
+    /// independent lanes as they have bits.
+    pub fn apply<T>(self, a: T, b: T) -> T
+    where
+        T: BitAnd<Output = T> + BitOr<Output = T> + BitXor<Output = T>,
+    {
+        match self {
+            Kind::And => a & b,
+            Kind::Or => a | b,
+            Kind::Xor => a ^ b,
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Kind::And => "AND",
+            Kind::Or => "OR",
+            Kind::Xor => "XOR",
+        }
+        .fmt(f)
+    }
+}
+
+/// A gate with the wires of its inputs.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct Gate {
+    pub kind: Kind,
+    pub inputs: [usize; 2],
+}
+
+/// Wires driven by gates, the others being inputs. Wires are numbered in
+/// the order they are first seen.
+#[derive(Clone)]
+pub struct Netlist {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    drivers: Vec<Option<Gate>>,
+    // Driven wires, each after the inputs of its gate
+    order: Vec<usize>,
+}
+
+impl Netlist {
+    /// Connects the gates given as (output, kind, input, input), failing
+    /// on wires driven twice and on loops.
+    pub fn new<'a, I>(gates: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = (&'a str, Kind, &'a str, &'a str)>,
+    {
+        let mut netlist = Netlist {
+            names: vec![],
+            ids: HashMap::new(),
+            drivers: vec![],
+            order: vec![],
+        };
+
+        for (output, kind, a, b) in gates {
+            let inputs = [netlist.add_wire(a), netlist.add_wire(b)];
+            let output = netlist.add_wire(output);
+
+            if netlist.drivers[output].is_some() {
+                return Err(format!("wire {} is driven twice", netlist.names[output]));
+            }
+
+            netlist.drivers[output] = Some(Gate { kind, inputs });
+        }
+
+        netlist.sort()?;
+        Ok(netlist)
+    }
+
+    fn add_wire(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+
+        self.names.push(name.to_string());
+        self.drivers.push(None);
+        self.ids.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    // Depth first, each wire after its inputs. A wire met again while its
+    // inputs are being sorted closes a loop
+    fn sort(&mut self) -> Result<(), String> {
+        // Not seen, on the path from the wire being sorted, or sorted
+        let mut state = vec![0u8; self.names.len()];
+        let mut path: Vec<(usize, usize)> = vec![];
+
+        self.order.clear();
+
+        for start in 0..self.names.len() {
+            if self.drivers[start].is_none() || state[start] == 2 {
+                continue;
+            }
+
+            state[start] = 1;
+            path.push((start, 0));
+
+            while let Some((wire, next)) = path.last_mut() {
+                let wire = *wire;
+                let Some(&input) = self.drivers[wire].unwrap().inputs.get(*next) else {
+                    path.pop();
+                    state[wire] = 2;
+                    self.order.push(wire);
+                    continue;
+                };
+
+                *next += 1;
+
+                if self.drivers[input].is_none() || state[input] == 2 {
+                    continue;
+                }
+
+                if state[input] == 1 {
+                    let start = path.iter().position(|&(w, _)| w == input).unwrap();
+                    let names: Vec<&str> = path[start..]
+                        .iter()
+                        .rev()
+                        .map(|&(w, _)| self.names[w].as_str())
+                        .collect();
+
+                    return Err(format!("loop through {}", names.join(" -> ")));
+                }
+
+                state[input] = 1;
+                path.push((input, 0));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, wire: usize) -> &str {
+        &self.names[wire]
+    }
+
+    pub fn wire(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn driver(&self, wire: usize) -> Option<Gate> {
+        self.drivers[wire]
+    }
+
+    /// The wires driven by a gate, each after the inputs of its gate.
+    pub fn driven(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// The gate driving the wire as `a XOR b`, or its name for an input.
+    pub fn describe(&self, wire: usize) -> String {
+        match self.drivers[wire] {
+            Some(Gate {
+                kind,
+                inputs: [a, b],
+            }) => {
+                format!("{} {kind} {}", self.names[a], self.names[b])
+            }
+            None => self.names[wire].clone(),
+        }
+    }
+
+    /// Exchanges the gates driving two wires, unless that makes a loop.
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), String> {
+        self.drivers.swap(a, b);
+
+        let sorted = self.sort();
+
+        if sorted.is_err() {
+            self.drivers.swap(a, b);
+            self.sort().unwrap();
+        }
+
+        sorted
+    }
+
+    /// The wires of a bus like z00, z01..., least significant first.
+    pub fn bus(&self, prefix: char) -> Vec<usize> {
+        let mut bus: Vec<(u32, usize)> = self
+            .names
+            .iter()
+            .enumerate()
+            .filter_map(|(wire, name)| {
+                let index = name.strip_prefix(prefix)?.parse().ok()?;
+
+                Some((index, wire))
+            })
+            .collect();
+
+        bus.sort_unstable();
+        bus.into_iter().map(|(_, wire)| wire).collect()
+    }
+
+    /// Gives the value of each driven wire from the inputs already set.
+    pub fn evaluate<T>(&self, values: &mut [T])
+    where
+        T: Copy + BitAnd<Output = T> + BitOr<Output = T> + BitXor<Output = T>,
+    {
+        for &wire in &self.order {
+            let gate = self.drivers[wire].unwrap();
+
+            values[wire] = gate
+                .kind
+                .apply(values[gate.inputs[0]], values[gate.inputs[1]]);
+        }
+    }
+
+    /// Adds `x` and `y` if the netlist is an adder, or computes whatever it
+    /// computes on its x and y buses. Buses have any width, their values
+    /// are given as bits, least significant first.
+    #[cfg(test)]
+    pub fn simulate(&self, x: &[bool], y: &[bool]) -> Vec<bool> {
+        let mut values = vec![false; self.len()];
+
+        set_bus(&mut values, &self.bus('x'), x);
+        set_bus(&mut values, &self.bus('y'), y);
+        self.evaluate(&mut values);
+        get_bus(&values, &self.bus('z'))
+    }
+}
+
+// The bits missing from the value are left to 0
+#[cfg(test)]
+pub fn set_bus(values: &mut [bool], bus: &[usize], bits: &[bool]) {
+    for (&wire, &bit) in bus.iter().zip(bits) {
+        values[wire] = bit;
+    }
+}
+
+pub fn get_bus(values: &[bool], bus: &[usize]) -> Vec<bool> {
+    bus.iter().map(|&wire| values[wire]).collect()
+}
+
+/// The value of bits, least significant first, if it fits in 64 bits.
+pub fn to_number(bits: &[bool]) -> Option<u64> {
+    bits.iter()
+        .enumerate()
+        .filter(|(_, &bit)| bit)
+        .try_fold(0u64, |value, (bit, _)| {
+            Some(value | 1u64.checked_shl(u32::try_from(bit).ok()?)?)
+        })
+}
+
+// A ripple-carry adder, as the puzzle input before its wires were swapped
+#[cfg(test)]
+pub fn adder(width: usize) -> Vec<(String, Kind, String, String)> {
+    let mut gates = vec![];
+    let mut carry = String::new();
+
+    for i in 0..width {
+        let (x, y, z) = (
+            format!("x{i:0>2}"),
+            format!("y{i:0>2}"),
+            format!("z{i:0>2}"),
+        );
+        let sum = if i == 0 {
+            z.clone()
+        } else {
+            format!("s{i:0>2}")
+        };
+        let generate = format!("g{i:0>2}");
+
+        gates.push((sum.clone(), Kind::Xor, x.clone(), y.clone()));
+        gates.push((generate.clone(), Kind::And, x, y));
+
+        if i == 0 {
+            carry = generate;
+            continue;
+        }
+
+        let propagate = format!("p{i:0>2}");
+        let next = if i == width - 1 {
+            format!("z{width:0>2}")
+        } else {
+            format!("c{i:0>2}")
+        };
+
+        gates.push((z, Kind::Xor, sum.clone(), carry.clone()));
+        gates.push((propagate.clone(), Kind::And, sum, carry));
+        gates.push((next.clone(), Kind::Or, propagate, generate));
+        carry = next;
+    }
+
+    gates
+}
+
+#[cfg(test)]
+pub fn connect(gates: &[(String, Kind, String, String)]) -> Result<Netlist, String> {
+    Netlist::new(
+        gates
+            .iter()
+            .map(|(output, kind, a, b)| (output.as_str(), *kind, a.as_str(), b.as_str())),
+    )
+}
+
+#[test]
+fn check_netlist() {
+    // Operands from a linear congruential generator
+    let mut seed: u64 = 2024;
+    let mut random = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        seed >> 11
+    };
+
+    // Operands of up to 127 bits, so that their sum fits in a u128
+    let bits = |value: u128, width: usize| -> Vec<bool> {
+        (0..width).map(|bit| value >> bit & 1 == 1).collect()
+    };
+
+    for width in [2, 8, 45, 63, 64, 65, 100, 127] {
+        let netlist = connect(&adder(width)).unwrap();
+        let mask = u128::MAX >> (128 - width);
+
+        assert_eq!(netlist.bus('z').len(), width + 1);
+
+        for _ in 0..100 {
+            let x = ((random() as u128) << 64 | random() as u128) & mask;
+            let y = ((random() as u128) << 64 | random() as u128) & mask;
+
+            assert_eq!(
+                netlist.simulate(&bits(x, width), &bits(y, width)),
+                bits(x + y, width + 1)
+            );
+        }
+    }
+
+    assert_eq!(to_number(&bits(u64::MAX as u128, 64)), Some(u64::MAX));
+    assert_eq!(to_number(&bits(1 << 64, 65)), None);
+    assert_eq!(to_number(&bits(1, 65)), Some(1));
+
+    let mut gates = adder(4);
+
+    gates.push((
+        String::from("s01"),
+        Kind::And,
+        String::from("x01"),
+        String::from("y01"),
+    ));
+    assert_eq!(connect(&gates).err().unwrap(), "wire s01 is driven twice");
+
+    gates.pop();
+    gates[1].2 = String::from("c02");
+    assert_eq!(
+        connect(&gates).err().unwrap(),
+        "loop through g00 -> p01 -> c01 -> p02 -> c02"
+    );
+}
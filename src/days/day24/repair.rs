@@ -0,0 +1,383 @@
+use super::netlist::Netlist;
+use std::fmt::{self, Display, Formatter};
+
+// No input needs more, the search gives up after this many swaps
+const MAX_SWAPS: usize = 6;
+
+// Up to 64 test vectors, one per bit of each value
+struct Batch {
+    x: Vec<u64>,
+    y: Vec<u64>,
+    sum: Vec<u64>,
+}
+
+/// Checks a netlist against the sum of its x and y buses.
+struct Adder {
+    x: Vec<usize>,
+    y: Vec<usize>,
+    z: Vec<usize>,
+    batches: Vec<Batch>,
+}
+
+// The bits of the sum, least significant first, one more than the operands
+fn add(x: &[bool], y: &[bool]) -> Vec<bool> {
+    let mut carry = false;
+    let mut sum: Vec<bool> = x
+        .iter()
+        .zip(y)
+        .map(|(&a, &b)| {
+            let bit = a ^ b ^ carry;
+
+            carry = a & b | carry & (a ^ b);
+            bit
+        })
+        .collect();
+
+    sum.push(carry);
+    sum
+}
+
+impl Adder {
+    // The operands of the test vectors are bits, so that the buses have
+    // any width, the z bus holding the carry out
+    fn new(netlist: &Netlist) -> Result<Self, String> {
+        let x = netlist.bus('x');
+        let y = netlist.bus('y');
+        let z = netlist.bus('z');
+        let width = x.len();
+
+        if width == 0 || y.len() != width || z.len() != width + 1 {
+            return Err(format!(
+                "{} bits of x, {} of y and {} of z do not make an adder",
+                x.len(),
+                y.len(),
+                z.len()
+            ));
+        }
+
+        let bits = |ones: std::ops::Range<usize>| -> Vec<bool> {
+            (0..width).map(|bit| ones.contains(&bit)).collect()
+        };
+        let mut tests = vec![];
+
+        // Each bit alone and with a carry coming in, then random operands
+        // from a linear congruential generator
+        for bit in 0..width {
+            tests.extend([
+                (bits(bit..bit + 1), bits(0..0)),
+                (bits(0..0), bits(bit..bit + 1)),
+                (bits(bit..bit + 1), bits(bit..bit + 1)),
+                (bits(0..bit + 1), bits(0..1)),
+                (bits(0..1), bits(0..bit + 1)),
+            ]);
+        }
+
+        let mut seed: u64 = 24;
+        let mut random = || -> Vec<bool> {
+            (0..width)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    seed >> 63 == 1
+                })
+                .collect()
+        };
+
+        for _ in 0..32 {
+            tests.push((random(), random()));
+        }
+
+        // The bits of the vectors are transposed so that the netlist is
+        // evaluated on 64 of them at once
+        let transpose = |values: &[Vec<bool>]| -> Vec<u64> {
+            (0..values[0].len())
+                .map(|bit| {
+                    values.iter().enumerate().fold(0, |lanes, (lane, value)| {
+                        lanes | (value[bit] as u64) << lane
+                    })
+                })
+                .collect()
+        };
+        let batches = tests
+            .chunks(64)
+            .map(|chunk| {
+                let (xs, ys): (Vec<Vec<bool>>, Vec<Vec<bool>>) = chunk.iter().cloned().unzip();
+                let sums: Vec<Vec<bool>> = chunk.iter().map(|(x, y)| add(x, y)).collect();
+
+                Batch {
+                    x: transpose(&xs),
+                    y: transpose(&ys),
+                    sum: transpose(&sums),
+                }
+            })
+            .collect();
+
+        Ok(Adder { x, y, z, batches })
+    }
+
+    // Whether the netlist gets each bit of the sum right for all the vectors
+    // of the batch
+    fn right_bits<'a>(
+        &'a self,
+        netlist: &Netlist,
+        values: &'a mut [u64],
+        batch: &'a Batch,
+    ) -> impl Iterator<Item = bool> + 'a {
+        for (bit, &wire) in self.x.iter().enumerate() {
+            values[wire] = batch.x[bit];
+        }
+
+        for (bit, &wire) in self.y.iter().enumerate() {
+            values[wire] = batch.y[bit];
+        }
+
+        netlist.evaluate(values);
+        self.z
+            .iter()
+            .zip(&batch.sum)
+            .map(|(&wire, &sum)| values[wire] == sum)
+    }
+
+    /// The lowest bit of the sum that the netlist gets wrong.
+    fn first_wrong_bit(&self, netlist: &Netlist) -> Option<usize> {
+        let mut values = vec![0; netlist.len()];
+
+        self.batches
+            .iter()
+            .filter_map(|batch| {
+                self.right_bits(netlist, &mut values, batch)
+                    .position(|right| !right)
+            })
+            .min()
+    }
+
+    // Stops at the first batch failing on these bits, unlike first_wrong_bit
+    fn is_right_up_to(&self, netlist: &Netlist, bit: usize) -> bool {
+        let mut values = vec![0; netlist.len()];
+
+        self.batches.iter().all(|batch| {
+            self.right_bits(netlist, &mut values, batch)
+                .take(bit + 1)
+                .all(|right| right)
+        })
+    }
+}
+
+/// Two wires whose gates were exchanged, with why.
+#[derive(Debug)]
+pub struct Swap {
+    pub wires: [String; 2],
+    gates: [String; 2],
+    bit: usize,
+    fixed_up_to: Option<usize>,
+}
+
+impl Display for Swap {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "bit {} of the sum is wrong, {} is driven by {} and {} by {}: swapping them makes ",
+            self.bit, self.wires[0], self.gates[0], self.wires[1], self.gates[1]
+        )?;
+
+        match self.fixed_up_to {
+            Some(bit) => write!(f, "bits 0 to {} right", bit - 1),
+            None => write!(f, "the adder right"),
+        }
+    }
+}
+
+// 1 for the wires the lower bits depend on, which are right, 2 for the
+// other wires the bit and the next one depend on, 0 for the rest
+fn cones(netlist: &Netlist, adder: &Adder, bit: usize) -> Vec<u8> {
+    let mut cone = vec![0; netlist.len()];
+
+    for (i, &z) in adder.z.iter().enumerate().take(bit + 2) {
+        let mark = if i < bit { 1 } else { 2 };
+        let mut stack = vec![z];
+
+        while let Some(wire) = stack.pop() {
+            if cone[wire] == 0 {
+                cone[wire] = mark;
+                stack.extend(
+                    netlist
+                        .driver(wire)
+                        .into_iter()
+                        .flat_map(|gate| gate.inputs),
+                );
+            }
+        }
+    }
+
+    cone
+}
+
+// Depth first search for at most `budget` swaps, each one of them making
+// more of the lower bits right
+fn search(netlist: &mut Netlist, adder: &Adder, budget: usize, swaps: &mut Vec<Swap>) -> bool {
+    let Some(bit) = adder.first_wrong_bit(netlist) else {
+        return true;
+    };
+
+    if budget == 0 {
+        return false;
+    }
+
+    // Swapping a wire the lower bits depend on would break them
+    let cone = cones(netlist, adder, bit);
+    let wires = |mark| {
+        netlist
+            .driven()
+            .iter()
+            .copied()
+            .filter(|&wire| cone[wire] == mark)
+            .collect::<Vec<usize>>()
+    };
+    let (suspects, others) = (wires(2), wires(0));
+    let local: Vec<(usize, usize)> = suspects
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &a)| suspects[i + 1..].iter().map(move |&b| (a, b)))
+        .collect();
+    let remote: Vec<(usize, usize)> = suspects
+        .iter()
+        .flat_map(|&a| others.iter().map(move |&b| (a, b)))
+        .collect();
+
+    // Swaps next to the wrong bit are tried first, the others only when
+    // none of them helps
+    for candidates in [local, remote] {
+        let mut helped = false;
+
+        for &(a, b) in &candidates {
+            if netlist.swap(a, b).is_err() {
+                continue;
+            }
+
+            if adder.is_right_up_to(netlist, bit) {
+                helped = true;
+                swaps.push(Swap {
+                    wires: [netlist.name(a).to_string(), netlist.name(b).to_string()],
+                    // Each wire is now driven by the gate of the other
+                    gates: [netlist.describe(b), netlist.describe(a)],
+                    bit,
+                    fixed_up_to: adder.first_wrong_bit(netlist),
+                });
+
+                if search(netlist, adder, budget - 1, swaps) {
+                    return true;
+                }
+
+                swaps.pop();
+            }
+
+            netlist.swap(a, b).unwrap();
+        }
+
+        if helped {
+            break;
+        }
+    }
+
+    false
+}
+
+/// A repair found bit by bit: swaps of gate outputs turning the netlist into
+/// an adder of its x and y buses into its z bus, lowest bits first. Each
+/// swap makes the lowest wrong bit right, which is not bound to give the
+/// fewest swaps.
+pub fn repair(netlist: &Netlist) -> Result<Vec<Swap>, String> {
+    let adder = Adder::new(netlist)?;
+    let mut netlist = netlist.clone();
+
+    for budget in 0..=MAX_SWAPS {
+        let mut swaps = vec![];
+
+        if search(&mut netlist, &adder, budget, &mut swaps) {
+            return Ok(swaps);
+        }
+    }
+
+    Err(format!("no adder within {MAX_SWAPS} swaps"))
+}
+
+#[test]
+fn check_repair() {
+    use super::netlist;
+
+    for (width, swapped) in [
+        (5, vec![("z00", "g00"), ("z03", "p03")]),
+        (8, vec![("z07", "z08")]),
+        (
+            45,
+            vec![
+                ("s12", "g12"),
+                ("c20", "z20"),
+                ("z30", "p30"),
+                ("z44", "g44"),
+            ],
+        ),
+    ] {
+        let mut gates = netlist::adder(width);
+        let name = |gates: &[(String, _, String, String)], wire: &str| {
+            gates.iter().position(|gate| gate.0 == wire).unwrap()
+        };
+
+        for (a, b) in &swapped {
+            let (i, j) = (name(&gates, a), name(&gates, b));
+            let output = gates[i].0.clone();
+
+            gates[i].0 = gates[j].0.clone();
+            gates[j].0 = output;
+        }
+
+        let netlist = netlist::connect(&gates).unwrap();
+        let swaps = repair(&netlist).unwrap();
+        let mut found: Vec<[String; 2]> = swaps.iter().map(|swap| swap.wires.clone()).collect();
+        let mut expected: Vec<[String; 2]> = swapped
+            .iter()
+            .map(|&(a, b)| {
+                let mut pair = [a.to_string(), b.to_string()];
+
+                pair.sort();
+                pair
+            })
+            .collect();
+
+        found.iter_mut().for_each(|pair| pair.sort());
+        found.sort();
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    let netlist = netlist::connect(&netlist::adder(4)).unwrap();
+
+    assert!(repair(&netlist).unwrap().is_empty());
+
+    let mut gates = netlist::adder(2);
+
+    gates.retain(|gate| !gate.2.starts_with('x'));
+    assert_eq!(
+        repair(&netlist::connect(&gates).unwrap()).err().unwrap(),
+        "0 bits of x, 0 of y and 2 of z do not make an adder"
+    );
+
+    // Adders wider than the 64 lanes of the batches
+    let mut gates = netlist::adder(70);
+    let (i, j) = (
+        gates.iter().position(|gate| gate.0 == "z66").unwrap(),
+        gates.iter().position(|gate| gate.0 == "g66").unwrap(),
+    );
+
+    assert!(repair(&netlist::connect(&gates).unwrap())
+        .unwrap()
+        .is_empty());
+    gates[i].0 = String::from("g66");
+    gates[j].0 = String::from("z66");
+
+    let swaps = repair(&netlist::connect(&gates).unwrap()).unwrap();
+
+    assert_eq!(swaps.len(), 1);
+    assert_eq!(swaps[0].bit, 66);
+}
@@ -1,10 +1,12 @@
-use advent_2024::Paragrapher;
+use advent_2024::{Answer, Paragrapher};
 use std::io::{BufRead, Lines};
 
 const W: usize = 5;
 const H: i32 = 5;
 
-fn resolve<T>(mut lines: Lines<T>) -> (usize, usize)
+type Schematics = (Vec<[i32; W]>, Vec<[i32; W]>);
+
+fn parse<T>(mut lines: Lines<T>) -> Schematics
 where
     T: BufRead,
 {
@@ -32,17 +34,25 @@ where
         }
     }
 
+    (locks, keys)
+}
+
+fn part1((locks, keys): &Schematics) -> usize {
     let mut p1 = 0;
 
     for l in locks {
-        for k in &keys {
+        for k in keys {
             let overlap = l.iter().zip(k.iter()).any(|(a, b)| a + b > H);
 
             p1 += if overlap { 0 } else { 1 };
         }
     }
 
-    (p1, 0)
+    p1
+}
+
+fn part2(_: &Schematics) -> Answer {
+    Answer::NoPart2
 }
 
 #[test]
@@ -88,15 +98,9 @@ fn check() {
 #####";
     use std::io::Cursor;
 
-    assert_eq!(resolve(Cursor::new(TEST).lines()), (3, 0));
-}
+    let input = parse(Cursor::new(TEST).lines());
 
-fn resolve_string<T>(lines: Lines<T>) -> (String, String)
-where
-    T: BufRead,
-{
-    let solution = resolve(lines);
-    (solution.0.to_string(), solution.1.to_string())
+    assert_eq!((part1(&input), part2(&input)), (3, Answer::NoPart2));
 }
 
-inventory::submit! { advent_2024::Day::new(file!(), resolve_string) }
+advent_2024::day!(parse, part1, part2);
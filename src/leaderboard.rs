@@ -0,0 +1,194 @@
+use crate::schedule;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[derive(Debug, Deserialize)]
+struct Star {
+    get_star_ts: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    id: u64,
+    name: Option<String>,
+    stars: u32,
+    local_score: u32,
+    #[serde(default)]
+    last_star_ts: u64,
+    #[serde(default)]
+    completion_day_level: BTreeMap<u32, BTreeMap<usize, Star>>,
+}
+
+impl Member {
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    // Seconds from the unlock to each star of the day
+    fn times(&self, day_number: u32) -> [Option<u64>; 2] {
+        let unlock = schedule::unlock_time(day_number)
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let level = self.completion_day_level.get(&day_number);
+
+        [1, 2].map(|part| {
+            level
+                .and_then(|stars| stars.get(&part))
+                .map(|star| star.get_star_ts.saturating_sub(unlock))
+        })
+    }
+}
+
+/// A private leaderboard as given by the API of Advent Of Code.
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    event: String,
+    members: BTreeMap<String, Member>,
+}
+
+/// Formats a duration as `hh:mm:ss`, hours going beyond a day.
+fn format_time(seconds: u64) -> String {
+    format!(
+        "{:0>2}:{:0>2}:{:0>2}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> io::Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// The members by decreasing score, the first to get their last star
+    /// first on a tie.
+    pub fn standings(&self) -> Vec<&Member> {
+        let mut members: Vec<&Member> = self.members.values().collect();
+
+        members.sort_by_key(|m| (std::cmp::Reverse(m.local_score), m.last_star_ts, m.id));
+        members
+    }
+
+    fn days(&self) -> Vec<u32> {
+        let mut days: Vec<u32> = self
+            .members
+            .values()
+            .flat_map(|m| m.completion_day_level.keys().copied())
+            .collect();
+
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+
+    /// The standings, then the time each member took to get the stars of
+    /// each day from its unlock, and between the two parts.
+    pub fn render(&self) -> String {
+        let standings = self.standings();
+        let width = standings.iter().map(|m| m.name().len()).max().unwrap_or(0);
+        let mut out = String::new();
+
+        let _ = writeln!(out, "Advent Of Code {}\n", self.event);
+
+        for (rank, member) in (1..).zip(&standings) {
+            let _ = writeln!(
+                out,
+                "{rank:>3}) {:>4} {:>2}* {}",
+                member.local_score,
+                member.stars,
+                member.name()
+            );
+        }
+
+        for day_number in self.days() {
+            let _ = writeln!(
+                out,
+                "\nDay {day_number:>2}  {:width$} {:>9} {:>9} {:>9}",
+                "", "part1", "part2", "delta"
+            );
+
+            let mut times: Vec<_> = standings
+                .iter()
+                .map(|m| (m, m.times(day_number)))
+                .filter(|(_, times)| times[0].is_some())
+                .collect();
+
+            times.sort_by_key(|&(_, [part1, part2])| (part2.is_none(), part2, part1));
+
+            for (member, [part1, part2]) in times {
+                let [part1, part2, delta] = [
+                    part1,
+                    part2,
+                    part1.zip(part2).map(|(part1, part2)| part2 - part1),
+                ]
+                .map(|t| t.map_or(String::from("-"), format_time));
+
+                let _ = writeln!(
+                    out,
+                    "        {:width$} {part1:>9} {part2:>9} {delta:>9}",
+                    member.name()
+                );
+            }
+        }
+
+        out
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "event": self.event,
+            "members": self.standings().iter().map(|m| json!({
+                "id": m.id,
+                "name": m.name(),
+                "local_score": m.local_score,
+                "stars": m.stars,
+                "days": m.completion_day_level.keys().map(|&day_number| {
+                    let [part1, part2] = m.times(day_number);
+
+                    (day_number.to_string(), json!({
+                        "part1": part1,
+                        "part2": part2,
+                        "delta": part1.zip(part2).map(|(part1, part2)| part2 - part1),
+                    }))
+                }).collect::<serde_json::Map<_, _>>(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// How long a downloaded leaderboard is reused, as asked by Advent Of Code.
+pub const REFRESH: Duration = Duration::from_secs(15 * 60);
+
+#[test]
+fn check_leaderboard() {
+    let leaderboard = Leaderboard::parse(include_str!("../fixtures/leaderboard.json")).unwrap();
+    let names: Vec<String> = leaderboard.standings().iter().map(|m| m.name()).collect();
+
+    assert_eq!(names, ["Ada", "Grace", "(anonymous user #1003)"]);
+    assert_eq!(format_time(93784), "26:03:04");
+
+    let rendered = leaderboard.render();
+    let lines: Vec<Vec<&str>> = rendered
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+
+    assert_eq!(lines[2], ["1)", "12", "4*", "Ada"]);
+    assert_eq!(lines[7], ["Ada", "00:05:12", "00:09:40", "00:04:28"]);
+    assert_eq!(lines[12], ["Grace", "06:46:40", "-", "-"]);
+
+    let json = leaderboard.to_json();
+
+    assert_eq!(json["members"][1]["days"]["1"]["delta"], 4200);
+    assert_eq!(json["members"][1]["days"]["2"]["part2"], Value::Null);
+}
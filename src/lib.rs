@@ -1,9 +1,35 @@
-use curl::easy::Easy;
+use serde_json::{json, Value};
+use std::any::Any;
 use std::cmp::{Eq, Ord, Ordering};
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, Lines, Write};
-use std::path::Path;
-use std::time::Instant;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, BufRead, Cursor, Lines};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub mod answer;
+mod aoc;
+pub mod config;
+pub mod leaderboard;
+pub mod perf;
+pub mod puzzle;
+pub mod schedule;
+pub mod stats;
+pub mod vault;
+
+pub use answer::{Answer, Answers};
+use aoc::Verdict;
+pub use aoc::{user_agent, URL};
+pub use config::Config;
+pub use leaderboard::Leaderboard;
+use schedule::{Clock, RateLimiter, SystemClock};
+pub use vault::Key;
 
 pub struct Paragraph<'a, T, F, O>
 where
@@ -60,29 +86,485 @@ where
     }
 }
 
+// Same as the main thread, some days keep big grids on the stack
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+// Days that timed out and are still running
+static BACKGROUND: AtomicUsize = AtomicUsize::new(0);
+
+enum Failure {
+    Panic(String),
+    Timeout(Duration),
+    /// The input could not be read, or downloaded when missing.
+    Input(io::Error),
+}
+
+impl Failure {
+    fn missing_input(&self) -> bool {
+        matches!(self, Failure::Input(error) if error.kind() == io::ErrorKind::NotFound)
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Failure::Panic(message) => write!(f, "panicked: {message}"),
+            Failure::Timeout(timeout) => write!(f, "timed out after {timeout:?}"),
+            Failure::Input(error) => error.fmt(f),
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic payload")
+    }
+}
+
+/// Which parts of a day should be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    Both,
+    Part1,
+    Part2,
+}
+
+impl Parts {
+    fn part1(&self) -> bool {
+        *self != Parts::Part2
+    }
+
+    fn part2(&self) -> bool {
+        *self != Parts::Part1
+    }
+}
+
+/// How the results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// Where the inputs are read from, and downloaded from when missing.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    pub directory: PathBuf,
+    pub session: Option<String>,
+    pub url: String,
+    pub user_agent: String,
+    /// Never download anything, a missing input skips its day.
+    pub offline: bool,
+    /// Where the puzzle pages are kept once downloaded.
+    pub cache: PathBuf,
+    /// Decrypts the inputs stored as `NN.txt.enc`, and the answers.
+    pub key: Option<Key>,
+}
+
+impl Inputs {
+    pub fn path(&self, day_number: u32) -> PathBuf {
+        self.directory.join(format!("{day_number:0>2}.txt"))
+    }
+}
+
+/// Settings shared by every day of a run.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Name of the profile the inputs and answers belong to, if any.
+    pub profile: Option<String>,
+    pub inputs: Inputs,
+    pub timeout: Option<Duration>,
+    pub parts: Parts,
+    pub format: Format,
+    pub answers: PathBuf,
+    /// Submit the answers that are not known yet.
+    pub submit: bool,
+    /// Run every variant of the days, not only the default one.
+    pub variants: bool,
+}
+
+pub type Input = Box<dyn Any + Send>;
+
+/// The lines of an input, read in memory as it may have been decrypted.
+pub type InputLines = Lines<Cursor<Vec<u8>>>;
+
+// How an answer compares to the known one, or to the one of the default
+// variant of the day
+enum Check {
+    Unknown,
+    Right,
+    Wrong(Answer),
+    Disagree(Answer),
+}
+
+struct Stage {
+    answer: Answer,
+    duration: Duration,
+    check: Check,
+}
+
+impl Stage {
+    fn time<F>(f: F) -> Self
+    where
+        F: FnOnce() -> Answer,
+    {
+        let start = Instant::now();
+        let answer = f();
+        let duration = start.elapsed();
+
+        Stage {
+            answer,
+            duration,
+            check: Check::Unknown,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "answer": self.answer.to_json(),
+            "time": self.duration.as_secs_f64(),
+            "correct": match self.check {
+                _ if matches!(self.answer, Answer::Unsolved(_)) => Value::from(false),
+                Check::Unknown => Value::Null,
+                Check::Right => Value::from(true),
+                Check::Wrong(_) | Check::Disagree(_) => Value::from(false),
+            },
+        })
+    }
+}
+
+struct Report {
+    parse: Duration,
+    parts: [Option<Stage>; 2],
+}
+
+fn label(day_number: u32, variant: &str) -> String {
+    if variant == DEFAULT {
+        format!("day{day_number:0>2}")
+    } else {
+        format!("day{day_number:0>2}/{variant}")
+    }
+}
+
+struct Row {
+    day_number: u32,
+    variant: &'static str,
+    duration: Duration,
+    result: Result<Report, Failure>,
+    // Timed while a day that timed out was still running
+    contended: bool,
+}
+
+impl Row {
+    fn failed(&self) -> bool {
+        match &self.result {
+            Ok(report) => report.parts.iter().flatten().any(|stage| {
+                matches!(stage.check, Check::Wrong(_) | Check::Disagree(_))
+                    || matches!(stage.answer, Answer::Unsolved(_))
+            }),
+            Err(_) => true,
+        }
+    }
+
+    // Compares the answers with the known ones, unknown ones are submitted
+    // when asked to and recorded if they are right.
+    fn check(&mut self, answers: &mut Answers, options: &Options) {
+        let Ok(report) = &mut self.result else {
+            return;
+        };
+
+        for (part, stage) in (1..=2).zip(report.parts.iter_mut()) {
+            let Some(stage) = stage else {
+                continue;
+            };
+
+            if let Some(expected) = answers.get(self.day_number, part) {
+                stage.check = if *expected == stage.answer {
+                    Check::Right
+                } else {
+                    Check::Wrong(expected.clone())
+                };
+                continue;
+            }
+
+            let (true, DEFAULT, Some(session), Some(submission)) = (
+                options.submit && !options.inputs.offline,
+                self.variant,
+                &options.inputs.session,
+                stage.answer.submission(),
+            ) else {
+                continue;
+            };
+            let inputs = &options.inputs;
+
+            match aoc::submit_answer(
+                &inputs.url,
+                &inputs.user_agent,
+                session,
+                self.day_number,
+                part,
+                &submission,
+            ) {
+                Ok(Verdict::Right) => {
+                    answers.set(self.day_number, part, stage.answer.clone());
+                    stage.check = Check::Right;
+                }
+                Ok(verdict) => eprintln!("day{:0>2}: part{part}: {verdict}", self.day_number),
+                Err(error) => eprintln!(
+                    "day{:0>2}: part{part}: unable to submit: {error}",
+                    self.day_number
+                ),
+            }
+        }
+    }
+
+    // Another variant of the same day must find the same answers
+    fn cross_check(&mut self, reference: &Row) {
+        let (Ok(report), Ok(reference)) = (&mut self.result, &reference.result) else {
+            return;
+        };
+
+        for (stage, reference) in report.parts.iter_mut().zip(reference.parts.iter()) {
+            if let (Some(stage), Some(reference)) = (stage, reference) {
+                if stage.answer != reference.answer && !matches!(stage.check, Check::Wrong(_)) {
+                    stage.check = Check::Disagree(reference.answer.clone());
+                }
+            }
+        }
+    }
+
+    fn print(&self, width: usize) {
+        let label = label(self.day_number, self.variant) + ":";
+        let w = width + 1;
+
+        match &self.result {
+            Ok(report) => {
+                let answers = report.parts.each_ref().map(|stage| match stage {
+                    Some(stage) => stage.answer.to_string(),
+                    None => String::from("-"),
+                });
+                let durations = report.parts.each_ref().map(|stage| match stage {
+                    Some(stage) => format!("{:?}", stage.duration),
+                    None => String::from("-"),
+                });
+
+                println!(
+                    "{label:w$} part1: {:20} part2: {:20} {:>12}{:>12}{:>12} in {:?}",
+                    answers[0],
+                    answers[1],
+                    format!("{:?}", report.parse),
+                    durations[0],
+                    durations[1],
+                    self.duration,
+                );
+
+                if self.contended {
+                    println!(
+                        "{:width$}  CONTENDED, a day that timed out was still running",
+                        ""
+                    );
+                }
+
+                for (part, stage) in (1..=2).zip(report.parts.iter()) {
+                    match stage.as_ref().map(|s| &s.check) {
+                        Some(Check::Wrong(expected)) => {
+                            println!("{:width$}  part{part}: WRONG, expected {expected}", "")
+                        }
+                        Some(Check::Disagree(answer)) => println!(
+                            "{:width$}  part{part}: WRONG, the default variant found {answer}",
+                            ""
+                        ),
+                        _ => (),
+                    }
+
+                    if let Some(Answer::Unsolved(reason)) = stage.as_ref().map(|s| &s.answer) {
+                        println!("{:width$}  part{part}: UNSOLVED, {reason}", "");
+                    }
+                }
+            }
+            Err(failure) if failure.missing_input() => {
+                println!("{label:w$} SKIPPED ({failure})");
+            }
+            Err(failure) => {
+                println!("{label:w$} FAILED ({failure})");
+            }
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match &self.result {
+            Ok(report) => json!({
+                "day": self.day_number,
+                "variant": self.variant,
+                "parse": report.parse.as_secs_f64(),
+                "part1": report.parts[0].as_ref().map(Stage::to_json),
+                "part2": report.parts[1].as_ref().map(Stage::to_json),
+                "time": self.duration.as_secs_f64(),
+                "contended": self.contended,
+            }),
+            Err(failure) => json!({
+                "day": self.day_number,
+                "variant": self.variant,
+                "error": failure.to_string(),
+                "skipped": failure.missing_input(),
+                "time": self.duration.as_secs_f64(),
+            }),
+        }
+    }
+}
+
+/// Name of the variant run unless asked otherwise.
+pub const DEFAULT: &str = "default";
+
+/// A day registers its three stages: `parse` turns the input into whatever
+/// the parts need, then `part1` and `part2` work independently on it. A day
+/// may register several named variants, they are expected to find the same
+/// answers. Use the [`day!`] macro rather than calling [`Day::new`] by hand.
 #[derive(Eq)]
 pub struct Day {
     day_filename: &'static str,
-    resolve: fn(Lines<BufReader<File>>) -> (String, String),
+    variant: &'static str,
+    parse: fn(InputLines) -> Input,
+    part1: fn(&Input) -> Answer,
+    part2: fn(&Input) -> Answer,
+}
+
+static EXAMPLE: AtomicBool = AtomicBool::new(false);
+
+#[doc(hidden)]
+pub fn is_example() -> bool {
+    EXAMPLE.load(AtomicOrdering::Relaxed)
+}
+
+/// Whether an example of the text is resolved rather than an input, as
+/// the tests and the `examples` command do.
+#[macro_export]
+macro_rules! example {
+    () => {
+        cfg!(test) || $crate::is_example()
+    };
+}
+
+/// Picks the value of a parameter of the puzzle: the first one for the
+/// examples of the text, the second one for an actual input.
+#[macro_export]
+macro_rules! parameter {
+    ($example:expr, $input:expr) => {
+        if $crate::example!() {
+            $example
+        } else {
+            $input
+        }
+    };
+}
+
+/// Registers a day from its `parse`, `part1` and `part2` functions,
+/// optionally preceded by the name of the variant. The parts may return
+/// anything that converts into an [`Answer`], a `Result` with a `String`
+/// error for the parts that can give up on an input.
+#[macro_export]
+macro_rules! day {
+    ($parse:path, $part1:path, $part2:path) => {
+        $crate::day!("default", $parse, $part1, $part2);
+    };
+    ($variant:literal, $parse:path, $part1:path, $part2:path) => {
+        inventory::submit! {
+            $crate::Day::new(
+                file!(),
+                $variant,
+                |lines| Box::new($parse(lines)),
+                |input| {
+                    let input = $crate::downcast(input, $parse);
+                    $part1(input).into()
+                },
+                |input| {
+                    let input = $crate::downcast(input, $parse);
+                    $part2(input).into()
+                },
+            )
+        }
+    };
+}
+
+// The parse function is only there to name the type of the parsed input
+#[doc(hidden)]
+pub fn downcast<I: 'static>(input: &Input, _parse: fn(InputLines) -> I) -> &I {
+    input.downcast_ref().unwrap()
 }
 
 impl Day {
     pub const fn new(
         day_filename: &'static str,
-        resolve: fn(Lines<BufReader<File>>) -> (String, String),
+        variant: &'static str,
+        parse: fn(InputLines) -> Input,
+        part1: fn(&Input) -> Answer,
+        part2: fn(&Input) -> Answer,
     ) -> Self {
         Day {
             day_filename,
-            resolve,
+            variant,
+            parse,
+            part1,
+            part2,
         }
     }
 
-    fn print(&self, session: Option<&str>) {
+    fn row(&'static self, options: &Options, lines: io::Result<InputLines>) -> Row {
+        let contended = BACKGROUND.load(AtomicOrdering::SeqCst) > 0;
         let start = Instant::now();
-        let (day_number, part1, part2) = self.resolve(session);
+        let result = lines
+            .map_err(Failure::Input)
+            .and_then(|lines| self.run(options, lines));
         let duration = start.elapsed();
 
-        println!("day{day_number:0>2}: part1: {part1:20} part2: {part2:20} in {duration:?}");
+        Row {
+            day_number: self.parse_number(),
+            variant: self.variant,
+            duration,
+            result,
+            contended,
+        }
+    }
+
+    // The day is resolved in its own thread so that a panic or an endless
+    // loop does not take the remaining days down with it. A day that times
+    // out is left running in the background until it ends or the process
+    // exits, taking CPU from the days timed meanwhile.
+    fn run(&'static self, options: &Options, lines: InputLines) -> Result<Report, Failure> {
+        let (tx, rx) = mpsc::channel();
+        let parts = options.parts;
+
+        thread::Builder::new()
+            .name(format!("day{:0>2}", self.parse_number()))
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                let result = panic::catch_unwind(|| self.resolve(lines, parts));
+
+                // Nobody waits for a day that timed out
+                if tx.send(result).is_err() {
+                    BACKGROUND.fetch_sub(1, AtomicOrdering::SeqCst);
+                }
+            })
+            .expect("unable to spawn a thread");
+
+        let result = match options.timeout {
+            Some(timeout) => match rx.recv_timeout(timeout) {
+                Ok(result) => result,
+                Err(RecvTimeoutError::Timeout) => {
+                    BACKGROUND.fetch_add(1, AtomicOrdering::SeqCst);
+                    return Err(Failure::Timeout(timeout));
+                }
+                Err(RecvTimeoutError::Disconnected) => unreachable!(),
+            },
+            None => rx.recv().unwrap(),
+        };
+
+        result.map_err(|payload| Failure::Panic(panic_message(payload)))
     }
 
     fn parse_number(&self) -> u32 {
@@ -92,22 +574,37 @@ impl Day {
             .unwrap()
     }
 
-    fn resolve(&self, session: Option<&str>) -> (u32, String, String) {
-        let day_number = self.parse_number();
-        let (part1, part2) = (self.resolve)(read_lines(session, day_number).unwrap());
-        (day_number, part1, part2)
+    fn resolve(&self, lines: InputLines, parts: Parts) -> Report {
+        let start = Instant::now();
+        let input = (self.parse)(lines);
+        let parse = start.elapsed();
+
+        Report {
+            parse,
+            parts: [
+                parts.part1().then(|| Stage::time(|| (self.part1)(&input))),
+                parts.part2().then(|| Stage::time(|| (self.part2)(&input))),
+            ],
+        }
+    }
+}
+
+impl Day {
+    // Sorts the default variant first
+    fn key(&self) -> (&'static str, bool, &'static str) {
+        (self.day_filename, self.variant != DEFAULT, self.variant)
     }
 }
 
 impl PartialEq for Day {
     fn eq(&self, other: &Self) -> bool {
-        self.day_filename == other.day_filename
+        self.key() == other.key()
     }
 }
 
 impl Ord for Day {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.day_filename.cmp(other.day_filename)
+        self.key().cmp(&other.key())
     }
 }
 
@@ -117,73 +614,654 @@ impl PartialOrd for Day {
     }
 }
 
-fn read_lines(
-    session: Option<&str>,
-    day_number: u32,
-) -> io::Result<io::Lines<io::BufReader<File>>> {
-    let filename = format!("./inputs/{day_number:0>2}.txt");
-    let path = Path::new(&filename);
-
-    if !path.exists() {
-        let session = session
-            .expect("set AOC session id using command line or AOC_SESSION environment variable");
-
-        println!("downloading input for day {day_number}");
-
-        // Try to create inputs directory
-        match std::fs::create_dir("./inputs") {
-            Ok(()) => {}
-            Err(error) => match error.kind() {
-                io::ErrorKind::AlreadyExists => {}
-                _ => panic!("unable to create ./inputs/ directory: {error}"),
-            },
-        }
+/// The lines of the input of the day, decrypted or downloaded if need be.
+pub fn read_lines(inputs: &Inputs, day_number: u32) -> io::Result<InputLines> {
+    let path = inputs.path(day_number);
+
+    if let Some(content) = vault::read(&path, inputs.key.as_ref())? {
+        return Ok(Cursor::new(content).lines());
+    }
 
-        let mut file = File::create(path)?;
-        let mut handle = Easy::new();
+    download(inputs, day_number)?;
+
+    Ok(Cursor::new(fs::read(path)?).lines())
+}
 
-        handle.cookie(&format!("session={session}"))?;
-        handle.url(&format!(
-            "https://adventofcode.com/2024/day/{day_number}/input"
-        ))?;
+// Nothing is downloaded offline or without a session, the input is then
+// reported missing
+fn session(inputs: &Inputs, day_number: u32) -> io::Result<&str> {
+    let missing = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("input {day_number:0>2} missing, {reason}"),
+        )
+    };
 
-        handle.write_function(move |data| Ok(file.write(data).unwrap()))?;
-        handle.perform()?;
+    match (&inputs.session, inputs.offline) {
+        (_, true) => Err(missing("not downloading it offline")),
+        (Some(session), false) => Ok(session),
+        (None, false) => Err(missing(
+            "set AOC session id using command line, AOC_SESSION environment variable or config file",
+        )),
     }
+}
+
+fn download(inputs: &Inputs, day_number: u32) -> io::Result<()> {
+    let session = session(inputs, day_number)?;
 
-    let file = File::open(path)?;
+    eprintln!("downloading input for day {day_number}");
 
-    Ok(io::BufReader::new(file).lines())
+    fs::create_dir_all(&inputs.directory).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!(
+                "unable to create {} directory: {error}",
+                inputs.directory.display()
+            ),
+        )
+    })?;
+
+    aoc::download_input(
+        &inputs.url,
+        &inputs.user_agent,
+        session,
+        day_number,
+        &inputs.path(day_number),
+    )
 }
 
-fn resolve_all(session: Option<&str>) {
-    let mut days: Vec<&'static Day> = inventory::iter::<Day>.into_iter().collect();
+const FETCH_THREADS: usize = 4;
+const FETCH_INTERVAL: Duration = Duration::from_secs(1);
 
-    days.sort_unstable();
+fn is_missing(inputs: &Inputs, day_number: u32) -> bool {
+    let path = inputs.path(day_number);
 
-    days.iter().for_each(|d| d.print(session));
+    !path.exists() && !vault::encrypted_path(&path).exists()
 }
 
-fn resolve_one(session: Option<&str>, day_number: u32) {
-    inventory::iter::<Day>
-        .into_iter()
-        .find(|d| d.parse_number() == day_number)
-        .expect("unable to find this day")
-        .print(session);
+// A few downloads at a time, no faster than one request a second
+fn download_all(missing: Vec<(&Inputs, u32)>, clock: &dyn Clock) -> io::Result<usize> {
+    let count = missing.len();
+    let queue = Mutex::new(VecDeque::from(missing));
+    let limiter = RateLimiter::new(FETCH_INTERVAL);
+    let failed = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..FETCH_THREADS.min(count) {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().pop_front();
+                let Some((inputs, day_number)) = next else {
+                    break;
+                };
+                let result = schedule::retry(clock, aoc::is_transient, || {
+                    limiter.wait(clock);
+                    download(inputs, day_number)
+                });
+
+                if let Err(error) = result {
+                    eprintln!("day{day_number:0>2}: {error}");
+                    failed.fetch_add(1, AtomicOrdering::Relaxed);
+                }
+            });
+        }
+    });
+
+    match failed.into_inner() {
+        0 => Ok(count),
+        failed => Err(io::Error::other(format!(
+            "unable to download {failed} of {count} inputs"
+        ))),
+    }
 }
 
-pub fn resolve(session: Option<&str>, days: &[u32]) {
-    let start = Instant::now();
+/// Downloads the missing inputs of the given days (all of them if empty)
+/// for each profile, skipping the puzzles not unlocked yet. Gives the
+/// number of inputs downloaded.
+pub fn fetch(profiles: &[Options], days: &[u32], clock: &dyn Clock) -> io::Result<usize> {
+    let days = if days.is_empty() {
+        (1..=25).collect()
+    } else {
+        days.to_vec()
+    };
+    let mut missing = vec![];
+
+    for options in profiles {
+        for &day_number in &days {
+            if is_missing(&options.inputs, day_number) && schedule::is_unlocked(clock, day_number) {
+                session(&options.inputs, day_number)?;
+                missing.push((&options.inputs, day_number));
+            }
+        }
+    }
+
+    download_all(missing, clock)
+}
+
+fn select_days(days: &[u32], variants: bool) -> Vec<&'static Day> {
+    let mut all: Vec<&'static Day> = inventory::iter::<Day>
+        .into_iter()
+        .filter(|d| variants || d.variant == DEFAULT)
+        .collect();
+
+    all.sort_unstable();
 
     if days.is_empty() {
-        resolve_all(session);
+        all
     } else {
-        days.iter().for_each(|&d| resolve_one(session, d));
+        days.iter()
+            .flat_map(|&day_number| {
+                let selected: Vec<_> = all
+                    .iter()
+                    .filter(|d| d.parse_number() == day_number)
+                    .copied()
+                    .collect();
+
+                assert!(!selected.is_empty(), "unable to find day {day_number}");
+
+                selected
+            })
+            .collect()
+    }
+}
+
+// Resolves the days against the inputs and answers of one profile
+fn resolve_profile(options: &Options, days: &[&'static Day], width: usize) -> io::Result<Vec<Row>> {
+    let mut answers = Answers::load(&options.answers, options.inputs.key.as_ref())?;
+    let mut rows: Vec<Row> = vec![];
+
+    for &day in days {
+        let mut row = day.row(options, read_lines(&options.inputs, day.parse_number()));
+
+        row.check(&mut answers, options);
+
+        if let Some(reference) = rows
+            .iter()
+            .find(|r| r.day_number == row.day_number && r.variant == DEFAULT)
+        {
+            row.cross_check(reference);
+        }
+
+        if options.format == Format::Text {
+            row.print(width);
+        }
+
+        rows.push(row);
+    }
+
+    answers.save()?;
+
+    Ok(rows)
+}
+
+/// Resolves the given days (all of them if empty) for each profile in turn
+/// and returns the number of days that panicked, timed out or gave a wrong
+/// answer. The profiles only differ by their inputs and answers, which
+/// fail the run when they cannot be read or saved.
+pub fn resolve(profiles: &[Options], days: &[u32]) -> io::Result<usize> {
+    let format = profiles[0].format;
+    let days = select_days(days, profiles[0].variants);
+
+    // Downloads happen before the timing starts, those that cannot be done
+    // here fail later with the day
+    let mut day_numbers: Vec<u32> = days.iter().map(|d| d.parse_number()).collect();
+
+    day_numbers.dedup();
+
+    let missing: Vec<_> = profiles
+        .iter()
+        .filter(|options| options.inputs.session.is_some() && !options.inputs.offline)
+        .flat_map(|options| day_numbers.iter().map(|&d| (&options.inputs, d)))
+        .filter(|&(inputs, day_number)| is_missing(inputs, day_number))
+        .collect();
+
+    if !missing.is_empty() {
+        if let Err(error) = download_all(missing, &SystemClock) {
+            eprintln!("{error}");
+        }
+    }
+
+    let start = Instant::now();
+    let width = days
+        .iter()
+        .map(|d| label(d.parse_number(), d.variant).len())
+        .max()
+        .unwrap_or(0);
+    let mut results = vec![];
+
+    if format == Format::Text {
+        println!(
+            "{:width$}{:57} {:>12}{:>12}{:>12}",
+            "", "", "parse", "part1", "part2"
+        );
+    }
+
+    for options in profiles {
+        if let (Format::Text, Some(profile), true) = (format, &options.profile, profiles.len() > 1)
+        {
+            println!("[{profile}]");
+        }
+
+        results.push((options, resolve_profile(options, &days, width)?));
     }
 
     let duration = start.elapsed();
+    let failed_rows = |rows: &[Row]| rows.iter().filter(|row| row.failed()).count();
+    let failed = results.iter().map(|(_, rows)| failed_rows(rows)).sum();
+    let mut skipped: Vec<u32> = results
+        .iter()
+        .flat_map(|(_, rows)| rows)
+        .filter(|row| matches!(&row.result, Err(failure) if failure.missing_input()))
+        .map(|row| row.day_number)
+        .collect();
+
+    skipped.sort_unstable();
+    skipped.dedup();
+
+    match format {
+        Format::Text if failed == 0 => println!("All done in {duration:?}"),
+        Format::Text => println!("All done in {duration:?}, {failed} failed"),
+        Format::Json if results.len() == 1 => println!(
+            "{:#}",
+            json!({
+                "days": results[0].1.iter().map(Row::to_json).collect::<Vec<_>>(),
+                "failed": failed,
+                "skipped": skipped,
+                "time": duration.as_secs_f64(),
+            })
+        ),
+        Format::Json => println!(
+            "{:#}",
+            json!({
+                "profiles": results
+                    .iter()
+                    .map(|(options, rows)| json!({
+                        "profile": options.profile,
+                        "days": rows.iter().map(Row::to_json).collect::<Vec<_>>(),
+                        "failed": failed_rows(rows),
+                    }))
+                    .collect::<Vec<_>>(),
+                "failed": failed,
+                "skipped": skipped,
+                "time": duration.as_secs_f64(),
+            })
+        ),
+    }
+
+    if format == Format::Text && !skipped.is_empty() {
+        println!(
+            "Skipped days with a missing input: {}",
+            skipped
+                .iter()
+                .map(|day_number| format!("{day_number:0>2}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    Ok(failed)
+}
+
+/// Resolves the days like [`resolve`], then times them `runs` more times
+/// against the inputs of each profile and appends the median time of
+/// each day to the history file, one record per profile.
+pub fn bench(profiles: &[Options], days: &[u32], runs: usize, history: &Path) -> io::Result<usize> {
+    let failed = resolve(profiles, days)?;
+
+    for options in profiles {
+        let mut times: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+
+        for _ in 0..runs {
+            for day in select_days(days, options.variants) {
+                let row = day.row(options, read_lines(&options.inputs, day.parse_number()));
+
+                // Not timed against a day running in the background
+                if row.result.is_ok() && !row.contended {
+                    times
+                        .entry(label(row.day_number, row.variant))
+                        .or_default()
+                        .push(row.duration);
+                }
+            }
+        }
+
+        let record = perf::Record {
+            commit: perf::git_commit(),
+            cpu: perf::host_cpu(),
+            threads: rayon::current_num_threads(),
+            profile: String::from(perf::build_profile()),
+            input_profile: options.profile.clone(),
+            date: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            runs,
+            days: times
+                .into_iter()
+                .map(|(label, mut durations)| {
+                    durations.sort_unstable();
+                    (label, durations[durations.len() / 2].as_secs_f64())
+                })
+                .collect(),
+        };
+
+        perf::append(history, &record)?;
+
+        eprintln!(
+            "Median of {runs} runs{}: {}, recorded in {} for {} on {}",
+            options
+                .profile
+                .as_ref()
+                .map_or(String::new(), |name| format!(" on the {name} inputs")),
+            perf::format_time(record.total()),
+            history.display(),
+            record.commit,
+            record.cpu
+        );
+    }
+
+    Ok(failed)
+}
+
+/// Waits for the puzzle of the day to unlock while showing a countdown,
+/// downloads the input of each profile as soon as it is out, then resolves
+/// the day. Gives the number of failed days like [`resolve`].
+pub fn await_day(profiles: &[Options], day_number: u32, clock: &dyn Clock) -> io::Result<usize> {
+    let mut waited = false;
+
+    schedule::wait_for_unlock(clock, day_number, |remaining| {
+        eprint!(
+            "\rday {day_number} unlocks in {} ",
+            schedule::format_remaining(remaining)
+        );
+        waited = true;
+    });
+
+    if waited {
+        eprintln!();
+    }
+
+    for options in profiles {
+        let inputs = &options.inputs;
+        let path = inputs.path(day_number);
+
+        if path.exists() || vault::encrypted_path(&path).exists() {
+            continue;
+        }
+
+        session(inputs, day_number)?;
+        schedule::retry(clock, aoc::is_transient, || download(inputs, day_number))?;
+    }
+
+    resolve(profiles, &[day_number])
+}
+
+/// Gives the page of the puzzle of the day, downloaded unless it is in the
+/// cache already or when asked to refresh it (to get the second part once
+/// the first one is solved). The page is kept in the cache along with its
+/// Markdown rendering.
+pub fn describe(inputs: &Inputs, day_number: u32, refresh: bool) -> io::Result<String> {
+    let path = inputs.cache.join(format!("{day_number:0>2}.html"));
+
+    if !refresh || inputs.offline {
+        match fs::read_to_string(&path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound && !inputs.offline => (),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Err(io::Error::new(
+                    error.kind(),
+                    format!("puzzle {day_number:0>2} missing, not downloading it offline"),
+                ))
+            }
+            result => return result,
+        }
+    }
+
+    eprintln!("downloading puzzle for day {day_number}");
+
+    let html = aoc::download_puzzle(
+        &inputs.url,
+        &inputs.user_agent,
+        inputs.session.as_deref(),
+        day_number,
+    )?;
+
+    fs::create_dir_all(&inputs.cache)?;
+    fs::write(&path, &html)?;
+    fs::write(
+        path.with_extension("md"),
+        puzzle::render(&html, puzzle::Style::Markdown),
+    )?;
+
+    Ok(html)
+}
+
+/// The private leaderboard, downloaded again only once its copy in the
+/// cache is older than [`leaderboard::REFRESH`].
+pub fn leaderboard(inputs: &Inputs, id: &str) -> io::Result<Leaderboard> {
+    let path = inputs.cache.join(format!("leaderboard-{id}.json"));
+    let fresh = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| {
+            modified
+                .elapsed()
+                .is_ok_and(|age| age < leaderboard::REFRESH)
+        });
+
+    if fresh || inputs.offline {
+        match fs::read_to_string(&path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound && inputs.offline => {
+                return Err(io::Error::new(
+                    error.kind(),
+                    format!("leaderboard {id} missing, not downloading it offline"),
+                ))
+            }
+            result => return Leaderboard::parse(&result?),
+        }
+    }
+
+    let session = inputs.session.as_deref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("leaderboard {id} missing, set AOC session id using command line, AOC_SESSION environment variable or config file"),
+        )
+    })?;
+
+    eprintln!("downloading leaderboard {id}");
+
+    let json = aoc::download_leaderboard(&inputs.url, &inputs.user_agent, session, id)?;
+    let leaderboard = Leaderboard::parse(&json)?;
+
+    fs::create_dir_all(&inputs.cache)?;
+    fs::write(&path, &json)?;
+
+    Ok(leaderboard)
+}
+
+/// Resolves the examples found in the puzzle of the day with the parameters
+/// of the examples, and checks the answers emphasised in the text. Gives
+/// the number of failed rows like [`resolve`].
+pub fn examples(options: &Options, day_number: u32) -> io::Result<usize> {
+    let html = describe(&options.inputs, day_number, false)?;
+    let mut runs: Vec<(String, Vec<usize>)> = vec![];
+    let mut expected = Answers::default();
+
+    for example in puzzle::expected_answers(&html) {
+        expected.set(day_number, example.part, Answer::parse(&example.answer));
+
+        // Both parts share their example more often than not
+        match runs.iter_mut().find(|(input, _)| *input == example.input) {
+            Some((_, parts)) => parts.push(example.part),
+            None => runs.push((example.input, vec![example.part])),
+        }
+    }
+
+    if runs.is_empty() {
+        return Err(io::Error::other(format!(
+            "no example with an answer found in puzzle {day_number:0>2}"
+        )));
+    }
+
+    let days = select_days(&[day_number], options.variants);
+    let width = days
+        .iter()
+        .map(|d| label(d.parse_number(), d.variant).len())
+        .max()
+        .unwrap_or(0);
+    let mut results = vec![];
+
+    EXAMPLE.store(true, AtomicOrdering::Relaxed);
+
+    if options.format == Format::Text {
+        println!(
+            "{:width$}{:57} {:>12}{:>12}{:>12}",
+            "", "", "parse", "part1", "part2"
+        );
+    }
+
+    for (input, parts) in &runs {
+        let options = Options {
+            parts: match parts[..] {
+                [1] => Parts::Part1,
+                [2] => Parts::Part2,
+                _ => Parts::Both,
+            },
+            submit: false,
+            ..options.clone()
+        };
+        let mut rows: Vec<Row> = vec![];
+
+        if options.format == Format::Text {
+            println!(
+                "[example of part {}]",
+                parts
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            );
+        }
+
+        for &day in &days {
+            let lines = Cursor::new(input.clone().into_bytes()).lines();
+            let mut row = day.row(&options, Ok(lines));
+
+            row.check(&mut expected, &options);
+
+            if options.format == Format::Text {
+                row.print(width);
+            }
+
+            rows.push(row);
+        }
+
+        results.push((input, parts, rows));
+    }
+
+    EXAMPLE.store(false, AtomicOrdering::Relaxed);
+
+    let failed = results
+        .iter()
+        .flat_map(|(_, _, rows)| rows)
+        .filter(|row| row.failed())
+        .count();
+
+    match options.format {
+        Format::Text if failed == 0 => println!("All examples right"),
+        Format::Text => println!("{failed} failed"),
+        Format::Json => println!(
+            "{:#}",
+            json!({
+                "examples": results
+                    .iter()
+                    .map(|(input, parts, rows)| json!({
+                        "input": input,
+                        "parts": parts,
+                        "days": rows.iter().map(Row::to_json).collect::<Vec<_>>(),
+                    }))
+                    .collect::<Vec<_>>(),
+                "failed": failed,
+            })
+        ),
+    }
+
+    Ok(failed)
+}
+
+/// Where each of the 25 days stands for the profile: its solutions in the
+/// registry, its input and its known answers.
+pub fn stats(options: &Options) -> io::Result<Vec<stats::Status>> {
+    let answers = Answers::load(&options.answers, options.inputs.key.as_ref())?;
+    let days = select_days(&[], true);
+    let mut statuses: Vec<stats::Status> = (1..=25)
+        .map(|day_number| stats::Status {
+            day_number,
+            variants: days
+                .iter()
+                .filter(|day| day.parse_number() == day_number)
+                .map(|day| day.variant)
+                .collect(),
+            input: !is_missing(&options.inputs, day_number),
+            stars: (1..=2)
+                .filter(|&part| {
+                    answers
+                        .get(day_number, part)
+                        .is_some_and(|answer| *answer != Answer::NoPart2)
+                })
+                .count(),
+        })
+        .collect();
+
+    // The last star is given for all the others
+    if statuses.iter().map(|status| status.stars).sum::<usize>() == 49 {
+        statuses[24].stars += 1;
+    }
+
+    Ok(statuses)
+}
+
+// The input of each given day (all of them if empty) then the answers
+fn stored_files(options: &Options, days: &[u32]) -> Vec<PathBuf> {
+    let days = if days.is_empty() {
+        (1..=25).collect()
+    } else {
+        days.to_vec()
+    };
+
+    days.into_iter()
+        .map(|day_number| options.inputs.path(day_number))
+        .chain([options.answers.clone()])
+        .collect()
+}
+
+fn required_key(options: &Options) -> io::Result<&Key> {
+    options.inputs.key.as_ref().ok_or_else(|| {
+        io::Error::other("set the key using AOC_KEY environment variable or config file")
+    })
+}
+
+/// Writes the encrypted counterpart of the inputs and answers found, which
+/// is safe to commit unlike the clear files.
+pub fn encrypt_inputs(options: &Options, days: &[u32]) -> io::Result<()> {
+    let key = required_key(options)?;
+
+    for path in stored_files(options, days) {
+        if path.exists() {
+            println!("{}", vault::encrypt_file(key, &path)?.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes back the clear inputs and answers from their encrypted files.
+pub fn decrypt_inputs(options: &Options, days: &[u32]) -> io::Result<()> {
+    let key = required_key(options)?;
+
+    for path in stored_files(options, days) {
+        if vault::encrypted_path(&path).exists() {
+            println!("{}", vault::decrypt_file(key, &path)?.display());
+        }
+    }
 
-    println!("All done in {duration:?}");
+    Ok(())
 }
 
 inventory::collect!(Day);
@@ -1,10 +1,30 @@
-use clap::Parser;
+use advent_2024::config::Profile;
+use advent_2024::perf;
+use advent_2024::puzzle::{self, Style};
+use advent_2024::schedule::SystemClock;
+use advent_2024::stats;
+use advent_2024::{Config, Format, Inputs, Key, Leaderboard, Options, Parts};
+use clap::{Parser, Subcommand};
 use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
 mod days;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "configuration file [default: ./advent.toml or ~/.config/advent_2024/config.toml]"
+    )]
+    config: Option<PathBuf>,
     #[arg(short, long, help = "limit the number of threads used by rayon")]
     nthreads: Option<usize>,
     #[arg(
@@ -13,21 +33,466 @@ struct Args {
         help = "Advent Of Code session ID for automatic downloading of inputs"
     )]
     session: Option<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "use the session, inputs and answers of this profile of the configuration, or of all of them"
+    )]
+    profile: Option<String>,
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "directory of the inputs [default: ./inputs]"
+    )]
+    inputs: Option<PathBuf>,
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_parser = seconds,
+        help = "give up on a day after this many seconds and go on with the next one, \
+                the days timed while it still runs are marked as contended"
+    )]
+    timeout: Option<Duration>,
+    #[arg(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(1..=2),
+        help = "only resolve this part of each day"
+    )]
+    part: Option<u8>,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        global = true,
+        help = "how to print the results [default: text]"
+    )]
+    format: Option<Format>,
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "file holding the known answers to check against [default: ./answers.txt]"
+    )]
+    answers: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        help = "never download nor submit anything, days without an input are skipped"
+    )]
+    offline: bool,
+    #[arg(long, help = "submit the answers that are not known yet")]
+    submit: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "run every variant of the days and check they agree (see also --timeout)"
+    )]
+    variants: bool,
+    #[arg(
+        long,
+        help = "time the days --runs more times and record the median in the history file"
+    )]
+    bench: bool,
+    #[arg(
+        long,
+        default_value_t = 10,
+        requires = "bench",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "how many times --bench times the days"
+    )]
+    runs: usize,
     #[arg(trailing_var_arg = true)]
     days: Vec<u32>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Manage the encrypted copies of the inputs and answers
+    Inputs {
+        #[command(subcommand)]
+        action: InputsAction,
+    },
+    /// Download the missing inputs ahead of the timing runs
+    Fetch {
+        #[arg(long, help = "every day unlocked so far")]
+        all: bool,
+        #[arg(
+            required_unless_present = "all",
+            conflicts_with = "all",
+            value_parser = clap::value_parser!(u32).range(1..=25)
+        )]
+        days: Vec<u32>,
+    },
+    /// Print the puzzle, then the examples found in it
+    Describe {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        #[arg(
+            long,
+            value_enum,
+            help = "how to render the puzzle [default: ansi on a terminal, markdown otherwise]"
+        )]
+        style: Option<Style>,
+        #[arg(long, help = "download the puzzle again, to get its second part")]
+        refresh: bool,
+    },
+    /// Resolve the examples of the puzzle and check the answers given in it
+    Examples {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Show the standings and the times of a private leaderboard
+    Leaderboard {
+        #[arg(help = "id of the leaderboard [default: leaderboard of the configuration]")]
+        id: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "id",
+            help = "read the leaderboard from this JSON file instead"
+        )]
+        file: Option<PathBuf>,
+    },
+    /// Show which days are solved, have an input and known answers
+    Stats,
+    /// Look back at the timings recorded by --bench
+    Perf {
+        #[command(subcommand)]
+        action: PerfAction,
+    },
+    /// Step through the program of day 17, reading commands from the standard input
+    Debug {
+        #[arg(
+            long,
+            help = "assembly of a program to run instead of the one of the input"
+        )]
+        asm: Option<PathBuf>,
+    },
+    /// Find the lowest A making the program of day 17 output the given values
+    Solve {
+        #[arg(help = "outputs separated by commas, the program itself by default")]
+        target: Option<String>,
+        #[arg(
+            long,
+            help = "assembly of a program to solve instead of the one of the input"
+        )]
+        asm: Option<PathBuf>,
+    },
+    /// Show the best paths through the maze of day 16 and the cheapest others
+    Paths {
+        #[arg(long, default_value_t = 1000, help = "score of a quarter turn")]
+        turn: usize,
+        #[arg(long, default_value_t = 1, help = "score of a step forward")]
+        step: usize,
+        #[arg(
+            long,
+            default_value_t = 100,
+            help = "list this many of the best paths at most, the others are only counted"
+        )]
+        best: usize,
+        #[arg(
+            short = 'k',
+            long,
+            default_value_t = 0,
+            help = "also list this many of the cheapest paths"
+        )]
+        alternatives: usize,
+    },
+    /// Type the codes of day 21 through a chain of keypads, the door's first
+    Chain {
+        #[arg(
+            required = true,
+            help = "numerical, directional or a file with a layout, then *N to repeat it"
+        )]
+        layouts: Vec<String>,
+        #[arg(long, help = "print the presses too, checked by replaying them")]
+        show: bool,
+    },
+    /// Explain which outputs of the day 24 adder are swapped
+    Repair,
+    /// Write the circuit of day 24 for Graphviz or a Verilog simulator
+    Export {
+        #[arg(value_enum)]
+        language: days::day24::Language,
+    },
+    /// Write a random map of the lab of day 6, to benchmark on more than the input
+    Lab {
+        #[arg(long, default_value_t = 1, help = "the same seed gives the same map")]
+        seed: u64,
+    },
+    /// Wait for the puzzle to unlock, download its input and resolve it
+    Await {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum InputsAction {
+    /// Write NN.txt.enc next to each input, and answers.txt.enc
+    Encrypt { days: Vec<u32> },
+    /// Write the clear inputs and answers back from their .enc files
+    Decrypt { days: Vec<u32> },
+}
+
+#[derive(Subcommand)]
+enum PerfAction {
+    /// Show the time of the day at each commit and where it regressed
+    History {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Write the Performance section of the README from the latest runs
+    Readme {
+        #[arg(default_value = "README.org")]
+        path: PathBuf,
+    },
+}
+
+// A number of seconds, possibly fractional, that a Duration can hold
+fn seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|_| format!("{s} is not a number"))?;
+
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("{s} is not a finite number of seconds from 0 on"))
+}
+
 fn main() {
     let args = Args::parse();
+    let config = Config::load(args.config.as_deref()).unwrap_or_else(|error| {
+        eprintln!("unable to read the configuration: {error}");
+        process::exit(2);
+    });
 
-    if let Some(nthreads) = args.nthreads {
+    if let Some(nthreads) = args.nthreads.or(config.threads) {
         rayon::ThreadPoolBuilder::new()
             .num_threads(nthreads)
             .build_global()
             .unwrap();
     }
 
-    let session = args.session.or(env::var("AOC_SESSION").ok());
+    let key = env::var("AOC_KEY")
+        .ok()
+        .or(config.key.clone())
+        .map(|passphrase| Key::new(&passphrase));
 
-    advent_2024::resolve(session.as_deref(), &args.days);
+    // The command line and the environment come first, then the profile
+    // and last the rest of the configuration
+    let options = |name: Option<&String>, profile: Option<&Profile>| Options {
+        profile: name.cloned(),
+        inputs: Inputs {
+            directory: args
+                .inputs
+                .clone()
+                .or(profile.and_then(|p| p.inputs.clone()))
+                .or(config.inputs.clone())
+                .unwrap_or(PathBuf::from("./inputs")),
+            session: args
+                .session
+                .clone()
+                .or(env::var("AOC_SESSION").ok())
+                .or(profile.and_then(|p| p.session.clone()))
+                .or(config.session.clone()),
+            url: config.url.clone().unwrap_or(String::from(advent_2024::URL)),
+            user_agent: advent_2024::user_agent(config.contact.as_deref()),
+            offline: args.offline || config.offline,
+            cache: config.cache.clone().unwrap_or(PathBuf::from("./cache")),
+            key: key.clone(),
+        },
+        timeout: args.timeout,
+        parts: match args.part {
+            Some(1) => Parts::Part1,
+            Some(2) => Parts::Part2,
+            _ => Parts::Both,
+        },
+        format: args.format.or(config.format).unwrap_or(Format::Text),
+        answers: args
+            .answers
+            .clone()
+            .or(profile.and_then(|p| p.answers.clone()))
+            .or(config.answers.clone())
+            .unwrap_or(PathBuf::from("./answers.txt")),
+        submit: args.submit,
+        variants: args.variants,
+    };
+
+    let profiles: Vec<Options> = match args.profile.as_deref() {
+        None => vec![options(None, None)],
+        Some("all") if config.profiles.is_empty() => {
+            eprintln!("no profile in the configuration");
+            process::exit(2);
+        }
+        Some("all") => config
+            .profiles
+            .iter()
+            .map(|(name, profile)| options(Some(name), Some(profile)))
+            .collect(),
+        Some(name) => match config.profiles.get_key_value(name) {
+            Some((name, profile)) => vec![options(Some(name), Some(profile))],
+            None => {
+                eprintln!(
+                    "unknown profile {name}, known ones: {}",
+                    config
+                        .profiles
+                        .keys()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                process::exit(2);
+            }
+        },
+    };
+
+    let history = config
+        .history
+        .clone()
+        .unwrap_or(PathBuf::from("./perf.jsonl"));
+
+    let result = match args.command {
+        Some(Command::Inputs {
+            action: InputsAction::Encrypt { days },
+        }) => profiles
+            .iter()
+            .try_for_each(|options| advent_2024::encrypt_inputs(options, &days)),
+        Some(Command::Inputs {
+            action: InputsAction::Decrypt { days },
+        }) => profiles
+            .iter()
+            .try_for_each(|options| advent_2024::decrypt_inputs(options, &days)),
+        Some(Command::Fetch { days, .. }) => advent_2024::fetch(&profiles, &days, &SystemClock)
+            .map(|count| {
+                eprintln!("{count} inputs downloaded");
+            }),
+        Some(Command::Describe {
+            day,
+            style,
+            refresh,
+        }) => advent_2024::describe(&profiles[0].inputs, day, refresh).map(|html| {
+            let style = style.unwrap_or(if io::stdout().is_terminal() {
+                Style::Ansi
+            } else {
+                Style::Markdown
+            });
+
+            print!("{}", puzzle::render(&html, style));
+
+            for (i, example) in puzzle::examples(&html).iter().enumerate() {
+                println!("\n--- example {} ---\n{example}", i + 1);
+            }
+        }),
+        Some(Command::Examples { day }) => advent_2024::examples(&profiles[0], day).map(|failed| {
+            if failed != 0 {
+                process::exit(1);
+            }
+        }),
+        Some(Command::Leaderboard { id, file }) => {
+            let leaderboard = match (file, id.or(config.leaderboard.clone())) {
+                (Some(path), _) => {
+                    fs::read_to_string(path).and_then(|json| Leaderboard::parse(&json))
+                }
+                (None, Some(id)) => advent_2024::leaderboard(&profiles[0].inputs, &id),
+                (None, None) => Err(io::Error::other(
+                    "no leaderboard given nor in the configuration",
+                )),
+            };
+
+            leaderboard.map(|leaderboard| match profiles[0].format {
+                Format::Text => print!("{}", leaderboard.render()),
+                Format::Json => println!("{}", leaderboard.to_json()),
+            })
+        }
+        Some(Command::Stats) => profiles.iter().try_for_each(|options| {
+            let statuses = advent_2024::stats(options)?;
+
+            if let Some(name) = options.profile.as_ref().filter(|_| profiles.len() > 1) {
+                println!("[{name}]");
+            }
+
+            match options.format {
+                Format::Text => print!("{}", stats::render(&statuses)),
+                Format::Json => println!("{}", stats::to_json(&statuses)),
+            }
+
+            Ok(())
+        }),
+        Some(Command::Perf { action }) => perf::load(&history).and_then(|records| match action {
+            PerfAction::History { day } => {
+                print!("{}", perf::history(&records, day));
+                Ok(())
+            }
+            PerfAction::Readme { path } => {
+                let readme = fs::read_to_string(&path)?;
+                let section = perf::readme_section(&records).ok_or_else(|| {
+                    io::Error::other("no release build benchmarked yet, see --bench")
+                })?;
+
+                fs::write(&path, perf::replace_section(&readme, &section))
+            }
+        }),
+        Some(Command::Debug { asm }) => asm.map(fs::read_to_string).transpose().and_then(|asm| {
+            // Hand-written programs start with zeroed registers without
+            // an input
+            let input = match advent_2024::read_lines(&profiles[0].inputs, 17) {
+                Err(_) if asm.is_some() => None,
+                result => Some(result?),
+            };
+
+            days::day17::debug(input, asm.as_deref(), io::stdin().lock(), io::stdout())
+        }),
+        Some(Command::Solve { target, asm }) => {
+            asm.map(fs::read_to_string).transpose().and_then(|asm| {
+                let input = match advent_2024::read_lines(&profiles[0].inputs, 17) {
+                    Err(_) if asm.is_some() => None,
+                    result => Some(result?),
+                };
+
+                days::day17::find_a(input, asm.as_deref(), target.as_deref(), io::stdout())
+            })
+        }
+        Some(Command::Paths {
+            turn,
+            step,
+            best,
+            alternatives,
+        }) => advent_2024::read_lines(&profiles[0].inputs, 16).and_then(|input| {
+            let costs = days::day16::Costs { turn, step };
+
+            days::day16::paths(input, costs, best, alternatives, io::stdout())
+        }),
+        Some(Command::Chain { layouts, show }) => advent_2024::read_lines(&profiles[0].inputs, 21)
+            .and_then(|input| days::day21::chain(input, &layouts, show, io::stdout())),
+        Some(Command::Repair) => advent_2024::read_lines(&profiles[0].inputs, 24)
+            .and_then(|input| days::day24::explain(input, io::stdout())),
+        Some(Command::Export { language }) => advent_2024::read_lines(&profiles[0].inputs, 24)
+            .and_then(|input| days::day24::export(input, language, io::stdout())),
+        Some(Command::Lab { seed }) => days::day06::lab(seed, io::stdout()),
+        Some(Command::Await { day }) => {
+            advent_2024::await_day(&profiles, day, &SystemClock).map(|failed| {
+                if failed != 0 {
+                    process::exit(1);
+                }
+            })
+        }
+        None => {
+            let failed = if args.bench {
+                advent_2024::bench(&profiles, &args.days, args.runs, &history)
+            } else {
+                advent_2024::resolve(&profiles, &args.days)
+            };
+
+            failed.map(|failed| {
+                if failed != 0 {
+                    process::exit(1);
+                }
+            })
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        process::exit(2);
+    }
 }
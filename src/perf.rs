@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+/// A day is taken as regressed when it got this much slower than at the
+/// previous commit.
+pub const REGRESSION: f64 = 1.2;
+
+/// The median times of one `--bench` run, along with what they depend on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub commit: String,
+    pub cpu: String,
+    pub threads: usize,
+    /// Build profile, `release` or `debug`.
+    pub profile: String,
+    /// Profile of the configuration the inputs come from, if any.
+    pub input_profile: Option<String>,
+    /// Unix time of the run.
+    pub date: u64,
+    pub runs: usize,
+    /// Median time of each day in seconds, by label (`day06/cells`).
+    pub days: BTreeMap<String, f64>,
+}
+
+impl Record {
+    /// The time of the default variants, that is of solving every day once.
+    pub fn total(&self) -> f64 {
+        self.days
+            .iter()
+            .filter(|(label, _)| !label.contains('/'))
+            .map(|(_, time)| time)
+            .sum()
+    }
+
+    // Times are only compared on the same host and build, with the same
+    // inputs
+    fn host(&self) -> (&str, usize, &str, Option<&str>) {
+        (
+            &self.cpu,
+            self.threads,
+            &self.profile,
+            self.input_profile.as_deref(),
+        )
+    }
+}
+
+/// The commit the sources were at, with `-dirty` when the working tree has
+/// changes.
+pub fn git_commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit)
+            if git(&["status", "--porcelain", "--untracked-files=no"])
+                .is_some_and(|status| !status.is_empty()) =>
+        {
+            format!("{commit}-dirty")
+        }
+        Some(commit) => commit,
+        None => String::from("unknown"),
+    }
+}
+
+/// The model of the processor, as found in `/proc/cpuinfo` on Linux: x86
+/// gives the processor, ARM boards only the board.
+pub fn host_cpu() -> String {
+    let arch = std::env::consts::ARCH;
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let field = |name: &str| {
+        cpuinfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+
+    match (field("model name"), field("Model")) {
+        (Some(model), _) if arch == "x86_64" || arch == "x86" => model,
+        (_, Some(board)) => format!("{board} ({arch})"),
+        (Some(model), None) => format!("{model} ({arch})"),
+        (None, None) => String::from(arch),
+    }
+}
+
+pub fn build_profile() -> &'static str {
+    if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+pub fn append(path: &Path, record: &Record) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+    writeln!(file, "{}", serde_json::to_string(record)?)
+}
+
+/// The records of the history file, oldest first. No file is no record.
+pub fn load(path: &Path) -> io::Result<Vec<Record>> {
+    let content = match fs::read_to_string(path) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        result => result?,
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {error}", path.display()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Formats seconds like `~40ms`, without more precision than the timings
+/// have.
+pub fn format_time(seconds: f64) -> String {
+    match seconds {
+        s if s >= 1.0 => format!("{s:.2}s"),
+        s if s >= 1e-3 => format!("{:.0}ms", s * 1e3),
+        s => format!("{:.0}µs", s * 1e6),
+    }
+}
+
+// The time of the day at each commit in order, the best of the runs when a
+// commit was benched several times
+fn trend<'a>(records: &[&'a Record], label: &str) -> Vec<(&'a str, f64)> {
+    let mut trend: Vec<(&str, f64)> = vec![];
+
+    for record in records {
+        let Some(&time) = record.days.get(label) else {
+            continue;
+        };
+
+        match trend
+            .iter_mut()
+            .find(|(commit, _)| *commit == record.commit)
+        {
+            Some((_, best)) => *best = best.min(time),
+            None => trend.push((&record.commit, time)),
+        }
+    }
+
+    trend
+}
+
+/// The indices of the points slower than the previous one by
+/// [`REGRESSION`] or more.
+pub fn regressions(trend: &[(&str, f64)]) -> Vec<usize> {
+    (1..trend.len())
+        .filter(|&i| trend[i].1 >= trend[i - 1].1 * REGRESSION)
+        .collect()
+}
+
+/// The time of each variant of the day at each commit, per host, with the
+/// commits that made it slower.
+pub fn history(records: &[Record], day_number: u32) -> String {
+    let prefix = format!("day{day_number:0>2}");
+    let mut hosts: BTreeMap<(&str, usize, &str, Option<&str>), Vec<&Record>> = BTreeMap::new();
+    let mut out = String::new();
+
+    for record in records {
+        hosts.entry(record.host()).or_default().push(record);
+    }
+
+    for ((cpu, threads, profile, input_profile), records) in hosts {
+        let inputs = input_profile.map_or(String::new(), |name| format!(", {name} inputs"));
+
+        let mut labels: Vec<&String> = records
+            .iter()
+            .flat_map(|record| record.days.keys())
+            .filter(|label| {
+                label
+                    .strip_prefix(&prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .collect();
+
+        labels.sort_unstable();
+        labels.dedup();
+
+        for label in labels {
+            let trend = trend(&records, label);
+            let regressed = regressions(&trend);
+
+            let _ = writeln!(
+                out,
+                "{label} on {cpu}, {threads} threads, {profile}{inputs}"
+            );
+
+            for (i, &(commit, time)) in trend.iter().enumerate() {
+                let change = match i {
+                    0 => String::new(),
+                    _ => format!("{:+.0}%", (time / trend[i - 1].1 - 1.0) * 100.0),
+                };
+                let mark = if regressed.contains(&i) {
+                    "  <- regression"
+                } else {
+                    ""
+                };
+
+                let line = format!("  {commit:14} {:>9} {change:>6}{mark}", format_time(time));
+
+                let _ = writeln!(out, "{}", line.trim_end());
+            }
+
+            let _ = match regressed.last() {
+                Some(&i) => writeln!(out, "  last regressed at {}\n", trend[i].0),
+                None => writeln!(out, "  no regression\n"),
+            };
+        }
+    }
+
+    if out.is_empty() {
+        out = format!("no benchmark of day {day_number} yet, see --bench\n");
+    }
+
+    out
+}
+
+/// The Performance section of the README, from the latest release run on
+/// each CPU, if there is one.
+pub fn readme_section(records: &[Record]) -> Option<String> {
+    let mut latest: BTreeMap<(&str, Option<&str>), &Record> = BTreeMap::new();
+    let mut out = String::from("** Performance\n");
+
+    for record in records.iter().filter(|record| record.profile == "release") {
+        latest.insert((&record.cpu, record.input_profile.as_deref()), record);
+    }
+
+    if latest.is_empty() {
+        return None;
+    }
+
+    for ((cpu, input_profile), record) in latest {
+        let inputs = input_profile.map_or(String::new(), |name| format!(", {name} inputs"));
+        let _ = write!(
+            out,
+            "\n*** {cpu}{inputs}\nIt takes ~{} to complete all puzzles ({} threads, median of {} runs at {}).\n\n",
+            format_time(record.total()),
+            record.threads,
+            record.runs,
+            record.commit
+        );
+
+        for (label, &time) in &record.days {
+            let _ = writeln!(out, "| {label} | {} |", format_time(time));
+        }
+    }
+
+    Some(out)
+}
+
+/// Replaces the Performance section of the README, up to the next section
+/// of the same level.
+pub fn replace_section(readme: &str, section: &str) -> String {
+    let Some(start) = readme.find("\n** Performance\n").map(|i| i + 1) else {
+        return format!("{}\n\n{section}", readme.trim_end());
+    };
+    let end = readme[start + 1..]
+        .find("\n** ")
+        .map_or(readme.len(), |i| start + i + 2);
+    let separator = if end < readme.len() { "\n" } else { "" };
+
+    format!("{}{section}{separator}{}", &readme[..start], &readme[end..])
+}
+
+#[test]
+fn check_perf() {
+    let record = |commit: &str, cpu: &str, day06: f64| Record {
+        commit: commit.to_string(),
+        cpu: cpu.to_string(),
+        threads: 12,
+        profile: String::from("release"),
+        input_profile: None,
+        date: 0,
+        runs: 10,
+        days: BTreeMap::from([
+            (String::from("day01"), 0.001),
+            (String::from("day06"), day06),
+            (String::from("day06/cells"), 0.1),
+        ]),
+    };
+    let records = [
+        record("aaaaaaa", "Ryzen", 0.010),
+        record("bbbbbbb", "Ryzen", 0.020),
+        record("bbbbbbb", "Ryzen", 0.011),
+        record("ccccccc", "Ryzen", 0.015),
+        record("ccccccc", "Pi", 0.300),
+    ];
+    let refs: Vec<&Record> = records.iter().filter(|r| r.cpu == "Ryzen").collect();
+    let trend = trend(&refs, "day06");
+
+    assert_eq!(
+        trend,
+        [("aaaaaaa", 0.010), ("bbbbbbb", 0.011), ("ccccccc", 0.015)]
+    );
+    assert_eq!(regressions(&trend), [2]);
+    assert!(history(&records, 6).contains("last regressed at ccccccc"));
+    assert_eq!(format_time(0.0404), "40ms");
+
+    let section = readme_section(&records).unwrap();
+
+    assert!(section.contains("*** Ryzen\nIt takes ~16ms to complete all puzzles"));
+    assert!(section.contains("| day06 | 300ms |"));
+
+    let mut alice = record("ccccccc", "Ryzen", 0.020);
+
+    alice.input_profile = Some(String::from("alice"));
+    assert!(history(&[records[3].clone(), alice.clone()], 6)
+        .contains("day06 on Ryzen, 12 threads, release, alice inputs\n  ccccccc"));
+    assert!(readme_section(&[alice])
+        .unwrap()
+        .contains("*** Ryzen, alice inputs\nIt takes ~21ms"));
+
+    let readme = "* Title\n** Performance\n*** Old\nslow\n** Next\ntext\n";
+
+    assert_eq!(
+        replace_section(readme, "** Performance\nfast\n"),
+        "* Title\n** Performance\nfast\n\n** Next\ntext\n"
+    );
+}
@@ -0,0 +1,420 @@
+use std::fmt::Write;
+
+/// How a puzzle description is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Style {
+    Markdown,
+    /// Text for the terminal, with escape sequences for the emphasis.
+    Ansi,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(&'a str),
+    Open(&'a str, &'a str),
+    Close(&'a str),
+}
+
+// Splits HTML into text, opening tags with their attributes and closing
+// tags. Comments are dropped.
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if let (Some(tag), Some(end)) = (rest.strip_prefix('<'), rest.find('>')) {
+            let tag = &tag[..end - 1];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                tokens.push(Token::Close(name.trim()));
+            } else {
+                let tag = tag.trim_end_matches('/');
+                let (name, attributes) = tag.split_once(' ').unwrap_or((tag, ""));
+
+                tokens.push(Token::Open(name, attributes));
+            }
+            rest = &rest[end + 1..];
+        } else {
+            let first = rest.chars().next().map_or(0, char::len_utf8);
+            let end = rest[first..].find('<').map_or(rest.len(), |i| i + first);
+
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    tokens
+}
+
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = attributes[start..].find('"')? + start;
+
+    Some(&attributes[start..end])
+}
+
+/// Replaces the character references by the characters.
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(';').filter(|&end| end <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let c = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+
+        match c {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The `<article>` elements of a puzzle page, one per part.
+pub fn articles(html: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<article") {
+        let Some(open_end) = rest[start..].find('>') else {
+            break;
+        };
+        let content = &rest[start + open_end + 1..];
+        let end = content.find("</article>").unwrap_or(content.len());
+
+        articles.push(&content[..end]);
+        rest = &content[end..];
+    }
+
+    articles
+}
+
+/// The text of the `<pre><code>` blocks of the puzzle, usually examples.
+pub fn examples(html: &str) -> Vec<String> {
+    let mut examples = vec![];
+    let mut current: Option<String> = None;
+
+    for token in articles(html).into_iter().flat_map(tokens) {
+        match (token, &mut current) {
+            (Token::Open("pre", _), None) => current = Some(String::new()),
+            (Token::Text(text), Some(example)) => example.push_str(&decode_entities(text)),
+            (Token::Close("pre"), Some(_)) => examples.extend(current.take()),
+            _ => (),
+        }
+    }
+
+    examples
+}
+
+/// An example of the text along with the answer it gives for a part.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub part: usize,
+    pub input: String,
+    pub answer: String,
+}
+
+/// Pairs the last answer emphasised in code in the text of each part with
+/// the last example before it, which is how most puzzles are written.
+pub fn expected_answers(html: &str) -> Vec<Example> {
+    let mut found = vec![];
+    let mut last_example: Option<String> = None;
+    let mut pre: Option<String> = None;
+    let (mut code, mut em) = (0, 0);
+    let mut answer = String::new();
+
+    for (part, article) in (1..=2).zip(articles(html)) {
+        let mut expected = None;
+
+        for token in tokens(article) {
+            match token {
+                Token::Open("pre", _) => pre = Some(String::new()),
+                Token::Close("pre") => last_example = pre.take(),
+                Token::Text(text) => match &mut pre {
+                    Some(pre) => pre.push_str(&decode_entities(text)),
+                    None if code > 0 && em > 0 => answer.push_str(&decode_entities(text)),
+                    None => (),
+                },
+                Token::Open("code", _) => code += 1,
+                Token::Open("em", _) => em += 1,
+                Token::Close(name @ ("code" | "em")) => {
+                    if name == "code" {
+                        code -= 1;
+                    } else {
+                        em -= 1;
+                    }
+
+                    if !answer.is_empty() {
+                        expected = last_example
+                            .clone()
+                            .map(|input| (input, answer.trim().to_string()));
+                        answer.clear();
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if let Some((input, answer)) = expected {
+            found.push(Example {
+                part,
+                input,
+                answer,
+            });
+        }
+    }
+
+    found
+}
+
+struct Renderer<'a> {
+    style: Style,
+    out: String,
+    pre: Option<String>,
+    code: bool,
+    links: Vec<&'a str>,
+    space: bool,
+}
+
+impl<'a> Renderer<'a> {
+    fn ansi(&self) -> bool {
+        self.style == Style::Ansi
+    }
+
+    // Whitespace is collapsed outside of <pre>, and dropped at the start of
+    // a line
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+
+        if let Some(pre) = &mut self.pre {
+            pre.push_str(&text);
+            return;
+        }
+
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.space = true;
+            } else {
+                self.flush_space();
+                self.out.push(c);
+            }
+        }
+    }
+
+    fn flush_space(&mut self) {
+        if self.space && !self.out.is_empty() && !self.out.ends_with(['\n', ' ']) {
+            self.out.push(' ');
+        }
+        self.space = false;
+    }
+
+    // Markers opening an inline element take the pending space before them
+    fn open(&mut self, marker: &str) {
+        match &mut self.pre {
+            Some(pre) => pre.push_str(marker),
+            None => {
+                self.flush_space();
+                self.out.push_str(marker);
+            }
+        }
+    }
+
+    fn close(&mut self, marker: &str) {
+        match &mut self.pre {
+            Some(pre) => pre.push_str(marker),
+            None => self.out.push_str(marker),
+        }
+    }
+
+    fn end_block(&mut self, newlines: usize) {
+        self.space = false;
+
+        let trailing = self.out.len() - self.out.trim_end_matches('\n').len();
+
+        if !self.out.is_empty() && trailing < newlines {
+            self.out.push_str(&"\n".repeat(newlines - trailing));
+        }
+    }
+
+    fn pre(&mut self, text: &str) {
+        self.end_block(2);
+
+        if self.ansi() {
+            for line in text.trim_end_matches('\n').lines() {
+                let _ = writeln!(self.out, "    \x1b[36m{line}\x1b[39m");
+            }
+        } else {
+            let _ = writeln!(self.out, "```\n{}\n```", text.trim_end_matches('\n'));
+        }
+
+        self.end_block(2);
+    }
+
+    fn token(&mut self, token: Token<'a>) {
+        let ansi = self.ansi();
+        let in_code = self.code || self.pre.is_some();
+
+        match token {
+            Token::Text(text) => self.text(text),
+            Token::Open("h2", _) => {
+                self.end_block(2);
+                self.open(if ansi { "\x1b[1;32m" } else { "## " });
+            }
+            Token::Close("h2") => {
+                self.close(if ansi { "\x1b[0m" } else { "" });
+                self.end_block(2);
+            }
+            Token::Open("p", _) => self.end_block(2),
+            Token::Close("p") | Token::Close("ul") => self.end_block(2),
+            Token::Open("li", _) => {
+                self.end_block(1);
+                self.open("- ");
+            }
+            Token::Close("li") => self.end_block(1),
+            Token::Open("pre", _) => {
+                self.end_block(2);
+                self.pre = Some(String::new());
+            }
+            Token::Close("pre") => {
+                let text = self.pre.take().unwrap_or_default();
+
+                self.pre(&text);
+            }
+            Token::Open("code", _) if self.pre.is_none() => {
+                self.open(if ansi { "\x1b[36m" } else { "`" });
+                self.code = true;
+            }
+            Token::Close("code") if self.pre.is_none() => {
+                self.close(if ansi { "\x1b[39m" } else { "`" });
+                self.code = false;
+            }
+            Token::Open("em", _) if ansi => self.open("\x1b[1m"),
+            Token::Close("em") if ansi => self.close("\x1b[22m"),
+            Token::Open("em", _) if !in_code => self.open("*"),
+            Token::Close("em") if !in_code => self.close("*"),
+            Token::Open("a", attributes) => {
+                self.links.push(attribute(attributes, "href").unwrap_or(""));
+                self.open(if ansi { "\x1b[4m" } else { "[" });
+            }
+            Token::Close("a") => {
+                let href = self.links.pop().unwrap_or("");
+
+                if ansi {
+                    self.close("\x1b[24m");
+                } else {
+                    self.close(&format!("]({href})"));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Renders the parts of the puzzle found in the page.
+pub fn render(html: &str, style: Style) -> String {
+    let mut renderer = Renderer {
+        style,
+        out: String::new(),
+        pre: None,
+        code: false,
+        links: vec![],
+        space: false,
+    };
+
+    for token in articles(html).into_iter().flat_map(tokens) {
+        renderer.token(token);
+    }
+
+    renderer.out.truncate(renderer.out.trim_end().len());
+    renderer.out.push('\n');
+    renderer.out
+}
+
+#[test]
+fn check_render() {
+    const HTML: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2><p>The
+<em>Chief Historian</em> is always present, see <a href="/2024/about">about</a>.</p>
+<ul><li>Pair up the <code>3</code> and the <code>4</code>.</li></ul>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>The total distance is <code><em>11</em></code> &amp; that's &lt;it&gt;.</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+</main></body></html>"#;
+
+    assert_eq!(
+        render(HTML, Style::Markdown),
+        "## --- Day 1: Historian Hysteria ---
+
+The *Chief Historian* is always present, see [about](/2024/about).
+
+- Pair up the `3` and the `4`.
+
+```
+3   4
+4   3
+```
+
+The total distance is `11` & that's <it>.
+"
+    );
+    assert!(render(HTML, Style::Ansi).contains("\x1b[1mChief Historian\x1b[22m"));
+    assert_eq!(examples(HTML), ["3   4\n4   3\n"]);
+    assert_eq!(
+        expected_answers(HTML),
+        [Example {
+            part: 1,
+            input: String::from("3   4\n4   3\n"),
+            answer: String::from("11"),
+        }]
+    );
+}
+
+#[test]
+fn check_tokens() {
+    assert_eq!(
+        tokens("<p>…µs</p>µ"),
+        [
+            Token::Open("p", ""),
+            Token::Text("…µs"),
+            Token::Close("p"),
+            Token::Text("µ"),
+        ]
+    );
+    assert_eq!(
+        render("<article><p>… and µs</p></article>", Style::Markdown),
+        "… and µs\n"
+    );
+}
@@ -0,0 +1,193 @@
+use std::io;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 2024-12-01 05:00:00 UTC, that is midnight US Eastern
+const FIRST_UNLOCK: u64 = 1_733_029_200;
+const DAY: u64 = 24 * 60 * 60;
+
+const RETRIES: u32 = 8;
+const FIRST_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Where the time comes from, so that waiting can be tested.
+pub trait Clock: Sync {
+    fn now(&self) -> SystemTime;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+/// When the puzzle of a day is released.
+pub fn unlock_time(day_number: u32) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(FIRST_UNLOCK + (day_number as u64 - 1) * DAY)
+}
+
+/// Whether the puzzle of a day is out.
+pub fn is_unlocked(clock: &dyn Clock, day_number: u32) -> bool {
+    clock.now() >= unlock_time(day_number)
+}
+
+/// Sleeps until the puzzle is released, calling `tick` with the remaining
+/// time about once a second.
+pub fn wait_for_unlock<F>(clock: &dyn Clock, day_number: u32, mut tick: F)
+where
+    F: FnMut(Duration),
+{
+    let unlock = unlock_time(day_number);
+
+    while let Ok(remaining) = unlock.duration_since(clock.now()) {
+        if remaining.is_zero() {
+            break;
+        }
+
+        tick(remaining);
+        clock.sleep(remaining.min(Duration::from_secs(1)));
+    }
+}
+
+/// Calls `f` until it succeeds, doubling the delay between attempts so as
+/// not to hammer the server. Gives the first error that is not `transient`
+/// right away, the last one after too many attempts.
+pub fn retry<T, F>(clock: &dyn Clock, transient: fn(&io::Error) -> bool, mut f: F) -> io::Result<T>
+where
+    F: FnMut() -> io::Result<T>,
+{
+    let mut delay = FIRST_DELAY;
+
+    for _ in 1..RETRIES {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(error) if !transient(&error) => return Err(error),
+            Err(error) => eprintln!("{error}, retrying in {delay:?}"),
+        }
+
+        clock.sleep(delay);
+        delay = (2 * delay).min(MAX_DELAY);
+    }
+
+    f()
+}
+
+/// Spaces out requests shared by several threads.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<SystemTime>>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        RateLimiter {
+            interval,
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Sleeps until the next free slot, which is then taken.
+    pub fn wait(&self, clock: &dyn Clock) {
+        let now = clock.now();
+        let slot = {
+            let mut next = self.next.lock().unwrap();
+            let slot = next.map_or(now, |next| next.max(now));
+
+            *next = Some(slot + self.interval);
+            slot
+        };
+
+        if let Ok(delay) = slot.duration_since(now) {
+            clock.sleep(delay);
+        }
+    }
+}
+
+/// Formats a countdown as `1h02m03s`.
+pub fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs_f64().ceil() as u64;
+
+    format!(
+        "{}h{:0>2}m{:0>2}s",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+struct FakeClock(Mutex<SystemTime>);
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> SystemTime {
+        *self.0.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        *self.0.lock().unwrap() += duration;
+    }
+}
+
+#[test]
+fn check_schedule() {
+    assert_eq!(
+        unlock_time(25),
+        UNIX_EPOCH + Duration::from_secs(1_735_102_800)
+    );
+
+    let clock = FakeClock(Mutex::new(unlock_time(3) - Duration::from_millis(2500)));
+    let mut ticks = vec![];
+
+    wait_for_unlock(&clock, 3, |remaining| {
+        ticks.push(format_remaining(remaining))
+    });
+
+    assert_eq!(ticks, ["0h00m03s", "0h00m02s", "0h00m01s"]);
+    assert_eq!(clock.now(), unlock_time(3));
+    assert!(is_unlocked(&clock, 3));
+    assert!(!is_unlocked(&clock, 4));
+
+    let transient = |error: &io::Error| error.kind() == io::ErrorKind::TimedOut;
+    let mut attempts = 0;
+    let result = retry(&clock, transient, || {
+        attempts += 1;
+
+        if attempts < 3 {
+            Err(io::Error::from(io::ErrorKind::TimedOut))
+        } else {
+            Ok(attempts)
+        }
+    });
+
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(clock.now(), unlock_time(3) + Duration::from_secs(3));
+
+    let mut attempts = 0;
+    let result: io::Result<()> = retry(&clock, transient, || {
+        attempts += 1;
+        Err(io::Error::from(io::ErrorKind::PermissionDenied))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(attempts, 1);
+    assert_eq!(clock.now(), unlock_time(3) + Duration::from_secs(3));
+    assert_eq!(format_remaining(Duration::from_secs(3723)), "1h02m03s");
+
+    let start = clock.now();
+    let limiter = RateLimiter::new(Duration::from_secs(2));
+
+    for _ in 0..3 {
+        limiter.wait(&clock);
+    }
+
+    assert_eq!(clock.now(), start + Duration::from_secs(4));
+}
@@ -0,0 +1,143 @@
+use crate::schedule;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::time::UNIX_EPOCH;
+
+// Midnight US Eastern is 05:00 UTC
+const EASTERN_OFFSET: u64 = 5 * 60 * 60;
+
+/// Where a day stands: whether it has a solution, an input and answers.
+#[derive(Debug)]
+pub struct Status {
+    pub day_number: u32,
+    /// Variants of the solution found in the registry, the default one
+    /// first.
+    pub variants: Vec<&'static str>,
+    /// Whether the input is there, in clear or encrypted.
+    pub input: bool,
+    /// How many answers are known, that is how many stars were earned.
+    pub stars: usize,
+}
+
+impl Status {
+    pub fn solved(&self) -> bool {
+        !self.variants.is_empty()
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "day": self.day_number,
+            "solved": self.solved(),
+            "variants": self.variants,
+            "input": self.input,
+            "stars": self.stars,
+        })
+    }
+}
+
+fn total_stars(statuses: &[Status]) -> usize {
+    statuses.iter().map(|status| status.stars).sum()
+}
+
+// 0 for Sunday, the days of the calendar starting at the unlock
+fn weekday(day_number: u32) -> usize {
+    let seconds = schedule::unlock_time(day_number)
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    // 1970-01-01 was a Thursday
+    ((seconds - EASTERN_OFFSET) / (24 * 60 * 60) + 4) as usize % 7
+}
+
+/// The stars of each day laid out as a calendar of December, one week per
+/// line.
+pub fn calendar(statuses: &[Status]) -> String {
+    let mut out = String::from(" Sun  Mon  Tue  Wed  Thu  Fri  Sat\n");
+    let mut column = weekday(1);
+
+    out.push_str(&"     ".repeat(column));
+
+    for day_number in 1..=25 {
+        let stars = statuses
+            .iter()
+            .find(|status| status.day_number == day_number)
+            .map_or(0, |status| status.stars);
+
+        let _ = write!(out, "{day_number:>3}{:2}", "*".repeat(stars));
+
+        column += 1;
+        if column == 7 {
+            column = 0;
+            out.truncate(out.trim_end().len());
+            out.push('\n');
+        }
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// A line per day, then the calendar and the number of stars.
+pub fn render(statuses: &[Status]) -> String {
+    let mut out = String::from("day  solved  input  stars  variants\n");
+
+    for status in statuses {
+        let _ = writeln!(
+            out,
+            " {:0>2}  {:6}  {:5}  {:5}  {}",
+            status.day_number,
+            if status.solved() { "yes" } else { "no" },
+            if status.input { "yes" } else { "no" },
+            "*".repeat(status.stars),
+            status.variants.join(", ")
+        );
+    }
+
+    let _ = write!(
+        out,
+        "\n{}\n{} stars out of 50\n",
+        calendar(statuses),
+        total_stars(statuses)
+    );
+    out
+}
+
+pub fn to_json(statuses: &[Status]) -> Value {
+    json!({
+        "days": statuses.iter().map(Status::to_json).collect::<Vec<_>>(),
+        "stars": total_stars(statuses),
+    })
+}
+
+#[test]
+fn check_stats() {
+    let statuses: Vec<Status> = (1..=25)
+        .map(|day_number| Status {
+            day_number,
+            variants: if day_number <= 3 {
+                vec!["default"]
+            } else {
+                vec![]
+            },
+            input: day_number <= 2,
+            stars: (3 - day_number.min(3)) as usize,
+        })
+        .collect();
+
+    assert_eq!(weekday(1), 0);
+    assert_eq!(weekday(25), 3);
+    assert_eq!(
+        calendar(&statuses),
+        " Sun  Mon  Tue  Wed  Thu  Fri  Sat
+  1**  2*   3    4    5    6    7
+  8    9   10   11   12   13   14
+ 15   16   17   18   19   20   21
+ 22   23   24   25
+"
+    );
+    assert!(render(&statuses).ends_with("\n3 stars out of 50\n"));
+    assert_eq!(to_json(&statuses)["days"][2]["solved"], true);
+    assert_eq!(to_json(&statuses)["days"][2]["input"], false);
+}
@@ -0,0 +1,161 @@
+use openssl::hash::MessageDigest;
+use openssl::pkcs5::pbkdf2_hmac;
+use openssl::rand::rand_bytes;
+use openssl::symm::{self, Cipher};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extension added to the name of an encrypted file.
+pub const EXTENSION: &str = "enc";
+
+const MAGIC: &[u8; 4] = b"AOC1";
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const ITERATIONS: usize = 100_000;
+
+/// AES-256 key derived from the passphrase shared by the team.
+#[derive(Clone)]
+pub struct Key([u8; 32]);
+
+impl Key {
+    pub fn new(passphrase: &str) -> Self {
+        let mut key = [0; 32];
+
+        pbkdf2_hmac(
+            passphrase.as_bytes(),
+            b"advent_2024",
+            ITERATIONS,
+            MessageDigest::sha256(),
+            &mut key,
+        )
+        .expect("unable to derive the key");
+
+        Key(key)
+    }
+}
+
+// Never print the key itself
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Encrypts with AES-256-GCM: the magic bytes, a random nonce, the cipher
+/// text and the authentication tag.
+pub fn encrypt(key: &Key, plain: &[u8]) -> io::Result<Vec<u8>> {
+    let mut nonce = [0; NONCE_LEN];
+    let mut tag = [0; TAG_LEN];
+
+    rand_bytes(&mut nonce)?;
+
+    let cipher = symm::encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key.0,
+        Some(&nonce),
+        MAGIC,
+        plain,
+        &mut tag,
+    )?;
+
+    Ok([&MAGIC[..], &nonce, &cipher, &tag].concat())
+}
+
+pub fn decrypt(key: &Key, data: &[u8]) -> io::Result<Vec<u8>> {
+    let data = data
+        .strip_prefix(MAGIC)
+        .filter(|d| d.len() >= NONCE_LEN + TAG_LEN)
+        .ok_or_else(|| invalid("not an encrypted file"))?;
+    let (nonce, rest) = data.split_at(NONCE_LEN);
+    let (cipher, tag) = rest.split_at(rest.len() - TAG_LEN);
+
+    symm::decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key.0,
+        Some(nonce),
+        MAGIC,
+        cipher,
+        tag,
+    )
+    .map_err(|_| invalid("wrong key or corrupted file"))
+}
+
+/// Path of the encrypted counterpart of a file: `01.txt` gives `01.txt.enc`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+
+    name.push(".");
+    name.push(EXTENSION);
+
+    PathBuf::from(name)
+}
+
+/// Reads a file, or else decrypts its encrypted counterpart if there is
+/// one. Gives `Ok(None)` when neither exists.
+pub fn read(path: &Path, key: Option<&Key>) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(content) => return Ok(Some(content)),
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        Err(_) => (),
+    }
+
+    let encrypted = encrypted_path(path);
+
+    match fs::read(&encrypted) {
+        Ok(content) => {
+            let key = key.ok_or_else(|| {
+                io::Error::other(format!(
+                    "{} is encrypted, set the key using AOC_KEY environment variable or config file",
+                    encrypted.display()
+                ))
+            })?;
+
+            decrypt(key, &content).map(Some).map_err(|error| {
+                io::Error::new(error.kind(), format!("{}: {error}", encrypted.display()))
+            })
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Encrypts `path` into `path.enc`, the clear file is left as is.
+pub fn encrypt_file(key: &Key, path: &Path) -> io::Result<PathBuf> {
+    let encrypted = encrypted_path(path);
+
+    fs::write(&encrypted, encrypt(key, &fs::read(path)?)?)?;
+
+    Ok(encrypted)
+}
+
+/// Decrypts `path.enc` back into `path`.
+pub fn decrypt_file(key: &Key, path: &Path) -> io::Result<PathBuf> {
+    let encrypted = encrypted_path(path);
+    let plain = decrypt(key, &fs::read(&encrypted)?).map_err(|error| {
+        io::Error::new(error.kind(), format!("{}: {error}", encrypted.display()))
+    })?;
+
+    fs::write(path, plain)?;
+
+    Ok(path.to_path_buf())
+}
+
+#[test]
+fn check_vault() {
+    let key = Key::new("correct horse battery staple");
+    let data = encrypt(&key, b"1   3\n2   4\n").unwrap();
+
+    assert!(data.starts_with(MAGIC));
+    assert_eq!(decrypt(&key, &data).unwrap(), b"1   3\n2   4\n");
+    assert!(decrypt(&Key::new("wrong"), &data).is_err());
+    assert!(decrypt(&key, b"1   3\n").is_err());
+    assert_eq!(
+        encrypted_path(Path::new("inputs/01.txt")),
+        PathBuf::from("inputs/01.txt.enc")
+    );
+}